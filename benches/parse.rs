@@ -0,0 +1,105 @@
+//! Time and peak heap use of parsing a synthetic 500k-row export, run with `cargo bench --bench parse`
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt::Write,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use chrono::{Duration, NaiveDate};
+use yew_d3_example::model::{
+    parser::{self, bearable_date_format},
+    symptoms::{severity_scale::SeverityScales, symptom_aliases::SymptomAliases},
+};
+
+const ROWS: usize = 500_000;
+/// 10 symptoms at 4 times of day, 8 medications and 2 journal entries
+const ROWS_PER_DAY: usize = 50;
+/// The same chunk size as the web app's parse worker
+const CHUNK_ROWS: usize = 2000;
+const TIMES_OF_DAY: [&str; 4] = ["pre", "am", "mid", "pm"];
+const SEVERITIES: [&str; 4] = ["Mild", "Moderate", "Severe", "Unbearable"];
+
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn synthetic_export(rows: usize) -> String {
+    let first_day = NaiveDate::from_ymd(1990, 1, 1);
+    let mut csv = String::from("date,weekday,time of day,category,rating/amount,detail,notes\n");
+    for i in 0..rows {
+        let date = first_day + Duration::days((i / ROWS_PER_DAY) as i64);
+        let slot = i % ROWS_PER_DAY;
+        let (time_of_day, category, amount, detail, notes) = match slot {
+            0..=39 => {
+                let severity = (i * 7) % SEVERITIES.len();
+                let detail = format!("Symptom {} ({})", slot / 4, SEVERITIES[severity]);
+                let notes = if i % 13 == 0 { "Worse after a long drive" } else { "" };
+                (TIMES_OF_DAY[slot % 4], "Symptom", (severity + 1).to_string(), detail, notes)
+            }
+            40..=47 => ("am", "Meds/Supplements", "1".to_string(), format!("Medication {}", slot - 40), ""),
+            _ => ("", "Journal", String::new(), "Quiet day, some reading and a short walk".to_string(), ""),
+        };
+        writeln!(
+            csv,
+            "\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"",
+            bearable_date_format::format(&date),
+            date.format("%A"),
+            time_of_day,
+            category,
+            amount,
+            detail,
+            notes
+        )
+        .unwrap();
+    }
+    csv
+}
+
+fn measure<T>(name: &str, parse: impl FnOnce() -> T) {
+    let baseline = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let start = Instant::now();
+    let result = parse();
+    let elapsed = start.elapsed();
+    let peak = PEAK.load(Ordering::Relaxed) - baseline;
+    let retained = ALLOCATED.load(Ordering::Relaxed) - baseline;
+    drop(result);
+    println!(
+        "{:<14} {:>8.2?} {:>10.1} MiB peak {:>10.1} MiB retained",
+        name,
+        elapsed,
+        peak as f64 / (1024.0 * 1024.0),
+        retained as f64 / (1024.0 * 1024.0)
+    );
+}
+
+fn main() {
+    let csv_text = synthetic_export(ROWS);
+    println!("{} rows, {:.1} MiB of CSV", ROWS, csv_text.len() as f64 / (1024.0 * 1024.0));
+
+    measure("stream", || parser::stream_into_data_manager(csv::Reader::from_reader(csv_text.as_bytes()), SymptomAliases::default(), SeverityScales::default()).unwrap());
+    measure("chunked", || {
+        let mut chunked = parser::ChunkedParser::new(csv_text.clone(), SymptomAliases::default(), SeverityScales::default()).unwrap();
+        while chunked.parse_chunk(CHUNK_ROWS).unwrap() {}
+        chunked.finish()
+    });
+}
@@ -0,0 +1,82 @@
+use std::{env, fs, process};
+
+use chrono::naive::{MAX_DATE, MIN_DATE};
+use yew_d3_example::model::{
+    data_manager::DataManager,
+    parser,
+    query::EntryQuery,
+    symptoms::{severity_scale::{self, SeverityScales}, symptom_aliases::{self, SymptomAliases}},
+    tidy,
+    writer,
+};
+
+const USAGE: &str = "Usage: bearable_cli tidy <bearable-export.csv> [--json] [settings]
+       bearable_cli query <bearable-export.csv> <query, e.g. \"severity >= 3 and weekday in [Sat, Sun]\"> [settings]
+Settings, as JSON in the format the web app keeps them in local storage:
+       --aliases <aliases.json>  symptom renames to roll up
+       --scales <scales.json>    severity scales by symptom name";
+
+/// Renames and scales saved in the web app, so the output matches what it shows for the same export
+#[derive(Default)]
+struct Settings {
+    aliases: SymptomAliases,
+    scales: SeverityScales,
+}
+
+fn main() {
+    let mut args = env::args().skip(1).collect::<Vec<String>>();
+    let result = take_settings(&mut args).and_then(|settings| match args.iter().map(|a| a.as_str()).collect::<Vec<&str>>().as_slice() {
+        ["tidy", path] => tidy_export(path, false, settings),
+        ["tidy", path, "--json"] => tidy_export(path, true, settings),
+        ["query", path, query] => query_export(path, query, settings),
+        _ => Err(USAGE.to_string()),
+    });
+
+    match result {
+        Ok(output) => print!("{}", output),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Takes the `--aliases` and `--scales` options out of `args`, reading the files they name
+fn take_settings(args: &mut Vec<String>) -> Result<Settings, String> {
+    let mut settings = Settings::default();
+    while let Some(index) = args.iter().position(|arg| arg == "--aliases" || arg == "--scales") {
+        if index + 1 >= args.len() {
+            return Err(USAGE.to_string());
+        }
+        let path = args.remove(index + 1);
+        let json = fs::read_to_string(&path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+        if args.remove(index) == "--aliases" {
+            settings.aliases = symptom_aliases::from_json(&json).map_err(|e| format!("Failed to read aliases from {}: {}", path, e))?;
+        } else {
+            settings.scales = severity_scale::from_json(&json).map_err(|e| format!("Failed to read scales from {}: {}", path, e))?;
+        }
+    }
+    Ok(settings)
+}
+
+fn read_export(path: &str, settings: Settings) -> Result<DataManager, String> {
+    let reader = csv::Reader::from_path(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    parser::stream_into_data_manager(reader, settings.aliases, settings.scales).map_err(|e| format!("Failed to parse {}: {}", path, e))
+}
+
+fn tidy_export(path: &str, as_json: bool, settings: Settings) -> Result<String, String> {
+    let data_manager = read_export(path, settings)?;
+    let observations = tidy::observations(&data_manager);
+    if as_json {
+        tidy::to_tidy_json_string(&observations).map_err(|e| e.to_string())
+    } else {
+        tidy::to_tidy_csv_string(&observations).map_err(|e| e.to_string())
+    }
+}
+
+/// The matching symptom entries, written back out as a Bearable export
+fn query_export(path: &str, query: &str, settings: Settings) -> Result<String, String> {
+    let query = EntryQuery::parse(query).map_err(|e| format!("Couldn't read the query: {}", e))?;
+    let data_manager = read_export(path, settings)?.filtered(&query);
+    writer::to_bearable_csv_string(&data_manager, &data_manager.get_symptom_ids(), MIN_DATE..=MAX_DATE).map_err(|e| e.to_string())
+}
@@ -0,0 +1,6 @@
+use yew_agent::Threaded;
+use yew_d3_example::worker::ParseWorker;
+
+fn main() {
+    ParseWorker::register();
+}
@@ -2,6 +2,30 @@ use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(module = "/src/chart.js")]
 extern "C" {
+    #[wasm_bindgen(js_name = "clear_chart")]
+    pub fn clear_chart(selector: &str);
+
     #[wasm_bindgen(js_name = "show_chart")]
-    pub fn show_chart(chart: JsValue);
+    pub fn show_chart(selector: &str, chart: JsValue, on_brush: Option<&js_sys::Function>);
+
+    #[wasm_bindgen(js_name = "show_calendar_heatmap")]
+    pub fn show_calendar_heatmap(selector: &str, heatmap: JsValue, on_day_click: &js_sys::Function);
+
+    #[wasm_bindgen(js_name = "show_stacked_area")]
+    pub fn show_stacked_area(selector: &str, chart: JsValue);
+
+    #[wasm_bindgen(js_name = "show_severity_distribution")]
+    pub fn show_severity_distribution(selector: &str, chart: JsValue);
+}
+
+#[wasm_bindgen(module = "/src/download.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = "download_text")]
+    pub fn download_text(filename: &str, mime_type: &str, text: &str);
+
+    #[wasm_bindgen(js_name = "download_chart_svg")]
+    pub fn download_chart_svg(selector: &str, filename: &str);
+
+    #[wasm_bindgen(js_name = "download_chart_png")]
+    pub fn download_chart_png(selector: &str, filename: &str);
 }
@@ -1,14 +1,32 @@
-export function show_chart(data) {
+const TIME_OF_DAY_LABELS = { pre: 'Pre-dawn', am: 'Morning', mid: 'Afternoon', pm: 'Evening', allday: 'All day', '': '' };
+
+function tooltip() {
+  let tooltip = d3.select('body').select('.tooltip');
+  if (tooltip.empty()) {
+    tooltip = d3.select('body').append('div')
+      .attr('class', 'tooltip')
+      .style('opacity', 0);
+  }
+  return tooltip;
+}
+
+export function clear_chart(selector) {
+  d3.select(selector).selectAll('*').remove();
+}
+
+export function show_chart(selector, data, on_brush) {
   console.log("JavaScript received data:")
   console.log(data)
 
   const xValue = d => Date.parse(d.x);
   const xLabel = 'Time';
   const yValue = d => d.y;
-  const yLabel = 'Temperature';
+  const yLabel = 'Severity';
   const margin = { left: 120, right: 30, top: 20, bottom: 120 };
+  const formatDate = d3.timeFormat('%Y-%m-%d');
+  const formatTooltipDate = d3.timeFormat('%a %-d %b %Y');
 
-  const svg = d3.select('#chart');
+  const svg = d3.select(selector);
   svg.selectAll('*').remove();
   
   const width = svg.attr('width');
@@ -50,23 +68,351 @@ export function show_chart(data) {
     .tickPadding(15)
     .tickSize(-innerWidth);
 
+  const allPoints = data.series.flatMap(s => s.points);
+  const scales = data.series.map(s => s.scale);
+  // Ticks are only named when every series is on the same scale
+  const levels = scales.length && scales.every(s => JSON.stringify(s.levels) === JSON.stringify(scales[0].levels))
+    ? scales[0].levels
+    : [];
+
   xScale
-    .domain(d3.extent(data, xValue))
+    .domain(d3.extent(allPoints, xValue))
     .range([0, innerWidth])
     .nice();
 
   yScale
-    .domain([0, 4]/* d3.extent(data, yValue) */)
-    .range([innerHeight, 0])
-    .nice();
+    .domain([d3.min(scales, s => s.min) || 0, Math.max(d3.max(scales, s => s.max) || 4, d3.max(allPoints, yValue) || 0)])
+    .range([innerHeight, 0]);
+
+  if (levels.length) {
+    yAxis
+      .tickValues(levels.map(level => level.value))
+      .tickFormat(value => levels.find(level => level.value === value).label);
+  } else {
+    yScale.nice();
+  }
+
+  xAxisG.call(xAxis);
+  yAxisG.call(yAxis);
+
+  // The brush sits below the circles so they still receive hover events
+  if (on_brush) {
+    const brush = d3.brushX()
+      .extent([[0, 0], [innerWidth, innerHeight]])
+      .on('end', event => {
+        if (!event.selection) {
+          return;
+        }
+        const [start, end] = event.selection.map(xScale.invert);
+        on_brush(formatDate(start), formatDate(end));
+      });
+
+    g.append('g')
+      .attr('class', 'brush')
+      .call(brush);
+  }
 
-  g.selectAll('circle').data(data)
+  const tip = tooltip();
+
+  const seriesG = g.selectAll('.series').data(data.series)
+    .enter().append('g')
+    .attr('class', 'series')
+    .attr('fill', s => s.color);
+
+  seriesG.selectAll('circle').data(s => s.points.map(p => ({ ...p, name: s.name, scale: s.scale })))
     .enter().append('circle')
     .attr('cx', d => xScale(xValue(d)))
     .attr('cy', d => yScale(yValue(d)))
     .attr('fill-opacity', 0.6)
-    .attr('r', 8);
+    .attr('r', 8)
+    .on('mouseover', (event, d) => {
+      tip.selectAll('*').remove();
+      tip.append('strong').text(d.name);
+      tip.append('div').text(`${formatTooltipDate(new Date(xValue(d)))} ${TIME_OF_DAY_LABELS[d.time_of_day] || d.time_of_day}`);
+      const level = d.scale.levels.find(level => level.value === d.y);
+      tip.append('div').text(`Severity: ${d.y}${level ? ` (${level.label})` : ''}`);
+      if (d.notes) {
+        tip.append('em').text(d.notes);
+      }
+      tip.style('left', `${event.pageX + 12}px`)
+        .style('top', `${event.pageY - 12}px`)
+        .style('opacity', 1);
+    })
+    .on('mouseout', () => tip.style('opacity', 0));
 
-  xAxisG.call(xAxis);
-  yAxisG.call(yAxis);
+  const legendG = g.append('g')
+    .attr('class', 'legend')
+    .attr('transform', `translate(0, ${innerHeight + 50})`);
+
+  const legendItems = legendG.selectAll('.legend-item').data(data.series)
+    .enter().append('g')
+    .attr('class', 'legend-item')
+    .attr('transform', (s, i) => `translate(${i * 160}, 0)`);
+
+  legendItems.append('circle')
+    .attr('r', 6)
+    .attr('fill', s => s.color);
+
+  legendItems.append('text')
+    .attr('x', 12)
+    .attr('dy', '0.35em')
+    .text(s => s.name);
+}
+
+export function show_calendar_heatmap(selector, data, on_day_click) {
+  console.log("JavaScript received heatmap:")
+  console.log(data)
+
+  const cellSize = 15;
+  const yearHeight = cellSize * 9;
+  const margin = { left: 60, right: 30, top: 40, bottom: 20 };
+  const parseDate = d3.timeParse('%Y-%m-%d');
+  const formatDate = d3.timeFormat('%Y-%m-%d');
+
+  const svg = d3.select(selector);
+  svg.selectAll('*').remove();
+
+  const valuesByDate = new Map(data.days.map(d => [d.date, d.value]));
+  const dates = data.days.map(d => parseDate(d.date));
+  const years = d3.range(d3.min(dates).getFullYear(), d3.max(dates).getFullYear() + 1);
+
+  const color = d3.scaleSequential(d3.interpolateReds)
+    .domain([0, Math.max(data.max_value, 1)]);
+
+  const g = svg.append('g')
+    .attr('transform', `translate(${margin.left},${margin.top})`);
+
+  g.append('text')
+    .attr('class', 'axis-label')
+    .attr('x', 0)
+    .attr('y', -15)
+    .text(data.name);
+
+  const yearG = g.selectAll('.year').data(years)
+    .enter().append('g')
+    .attr('class', 'year')
+    .attr('transform', (year, i) => `translate(0, ${i * yearHeight})`);
+
+  yearG.append('text')
+    .attr('x', -10)
+    .attr('y', cellSize * 3.5)
+    .attr('text-anchor', 'end')
+    .text(year => year);
+
+  yearG.selectAll('rect')
+    .data(year => d3.timeDays(new Date(year, 0, 1), new Date(year + 1, 0, 1)))
+    .enter().append('rect')
+    .attr('width', cellSize - 2)
+    .attr('height', cellSize - 2)
+    .attr('x', d => d3.timeSunday.count(d3.timeYear(d), d) * cellSize)
+    .attr('y', d => d.getDay() * cellSize)
+    .attr('fill', d => {
+      const value = valuesByDate.get(formatDate(d));
+      return value === undefined ? '#eee' : color(value);
+    })
+    .style('cursor', d => valuesByDate.has(formatDate(d)) ? 'pointer' : 'default')
+    .on('click', (event, d) => on_day_click(formatDate(d)))
+    .append('title')
+    .text(d => {
+      const value = valuesByDate.get(formatDate(d));
+      return value === undefined ? formatDate(d) : `${formatDate(d)}: ${value}`;
+    });
+}
+
+export function show_stacked_area(selector, data) {
+  console.log("JavaScript received stacked area:")
+  console.log(data)
+
+  const parseDate = d3.timeParse('%Y-%m-%d');
+  const margin = { left: 120, right: 30, top: 20, bottom: 120 };
+
+  const svg = d3.select(selector);
+  svg.selectAll('*').remove();
+
+  const width = svg.attr('width');
+  const height = svg.attr('height');
+  const innerWidth = width - margin.left - margin.right;
+  const innerHeight = height - margin.top - margin.bottom;
+
+  const keys = data.series.map(s => s.name);
+  const rows = data.days.map(d => {
+    const row = { date: parseDate(d.date) };
+    keys.forEach((key, i) => row[key] = d.values[i]);
+    return row;
+  });
+  const stacked = d3.stack().keys(keys)(rows);
+
+  const g = svg.append('g')
+    .attr('transform', `translate(${margin.left},${margin.top})`);
+
+  const xScale = d3.scaleTime()
+    .domain(d3.extent(rows, d => d.date))
+    .range([0, innerWidth]);
+
+  const yScale = d3.scaleLinear()
+    .domain([0, d3.max(stacked, layer => d3.max(layer, d => d[1])) || 1])
+    .range([innerHeight, 0])
+    .nice();
+
+  const area = d3.area()
+    .x(d => xScale(d.data.date))
+    .y0(d => yScale(d[0]))
+    .y1(d => yScale(d[1]));
+
+  g.selectAll('.layer').data(stacked)
+    .enter().append('path')
+    .attr('class', 'layer')
+    .attr('fill', (layer, i) => data.series[i].color)
+    .attr('fill-opacity', 0.8)
+    .attr('d', area)
+    .append('title')
+    .text(layer => layer.key);
+
+  g.append('g')
+    .attr('transform', `translate(0, ${innerHeight})`)
+    .call(d3.axisBottom(xScale).tickPadding(15));
+
+  const yAxisG = g.append('g')
+    .call(d3.axisLeft(yScale).tickPadding(15));
+
+  yAxisG.append('text')
+    .attr('class', 'axis-label')
+    .attr('x', -innerHeight / 2)
+    .attr('y', -60)
+    .attr('transform', `rotate(-90)`)
+    .style('text-anchor', 'middle')
+    .text('Total daily severity');
+
+  const legendItems = g.append('g')
+    .attr('class', 'legend')
+    .attr('transform', `translate(0, ${innerHeight + 50})`)
+    .selectAll('.legend-item').data(data.series)
+    .enter().append('g')
+    .attr('class', 'legend-item')
+    .attr('transform', (s, i) => `translate(${i * 160}, 0)`);
+
+  legendItems.append('rect')
+    .attr('width', 12)
+    .attr('height', 12)
+    .attr('y', -6)
+    .attr('fill', s => s.color);
+
+  legendItems.append('text')
+    .attr('x', 18)
+    .attr('dy', '0.35em')
+    .text(s => s.name);
+}
+
+export function show_severity_distribution(selector, data) {
+  console.log("JavaScript received severity distribution:")
+  console.log(data)
+
+  const margin = { left: 80, right: 30, top: 40, bottom: 80 };
+  const gap = 80;
+
+  const svg = d3.select(selector);
+  svg.selectAll('*').remove();
+
+  const width = svg.attr('width');
+  const height = svg.attr('height');
+  const panelWidth = (width - margin.left - margin.right - gap) / 2;
+  const innerHeight = height - margin.top - margin.bottom;
+
+  const g = svg.append('g')
+    .attr('transform', `translate(${margin.left},${margin.top})`);
+
+  g.append('text')
+    .attr('class', 'axis-label')
+    .attr('y', -15)
+    .text(data.name);
+
+  // Histogram of severities
+  const histogramG = g.append('g');
+
+  const xBand = d3.scaleBand()
+    .domain(data.histogram.map(b => b.severity))
+    .range([0, panelWidth])
+    .padding(0.1);
+
+  const yCount = d3.scaleLinear()
+    .domain([0, d3.max(data.histogram, b => b.count) || 1])
+    .range([innerHeight, 0])
+    .nice();
+
+  histogramG.selectAll('rect').data(data.histogram)
+    .enter().append('rect')
+    .attr('x', b => xBand(b.severity))
+    .attr('y', b => yCount(b.count))
+    .attr('width', xBand.bandwidth())
+    .attr('height', b => innerHeight - yCount(b.count))
+    .attr('fill', 'steelblue')
+    .append('title')
+    .text(b => `${severityLabel(b.severity)}: ${b.count} entries`);
+
+  const severityLabel = severity => (data.histogram.find(b => b.severity === severity) || {}).label || severity;
+
+  histogramG.append('g')
+    .attr('transform', `translate(0, ${innerHeight})`)
+    .call(d3.axisBottom(xBand).tickFormat(severityLabel))
+    .append('text')
+    .attr('class', 'axis-label')
+    .attr('x', panelWidth / 2)
+    .attr('y', 50)
+    .text('Severity');
+
+  histogramG.append('g')
+    .call(d3.axisLeft(yCount));
+
+  // Box plots per month
+  const boxG = g.append('g')
+    .attr('transform', `translate(${panelWidth + gap}, 0)`);
+
+  const xMonth = d3.scaleBand()
+    .domain(data.monthly.map(b => b.label))
+    .range([0, panelWidth])
+    .padding(0.3);
+
+  const ySeverity = d3.scaleLinear()
+    .domain(d3.extent(data.histogram, b => b.severity))
+    .range([innerHeight, 0])
+    .nice();
+
+  const boxes = boxG.selectAll('.box').data(data.monthly)
+    .enter().append('g')
+    .attr('class', 'box')
+    .attr('transform', b => `translate(${xMonth(b.label)}, 0)`);
+
+  boxes.append('line')
+    .attr('x1', xMonth.bandwidth() / 2)
+    .attr('x2', xMonth.bandwidth() / 2)
+    .attr('y1', b => ySeverity(b.min))
+    .attr('y2', b => ySeverity(b.max))
+    .attr('stroke', 'black');
+
+  boxes.append('rect')
+    .attr('width', xMonth.bandwidth())
+    .attr('y', b => ySeverity(b.q3))
+    .attr('height', b => ySeverity(b.q1) - ySeverity(b.q3))
+    .attr('fill', '#b3cde3')
+    .attr('stroke', 'black');
+
+  boxes.append('line')
+    .attr('x2', xMonth.bandwidth())
+    .attr('y1', b => ySeverity(b.median))
+    .attr('y2', b => ySeverity(b.median))
+    .attr('stroke', 'black')
+    .attr('stroke-width', 2);
+
+  boxes.append('title')
+    .text(b => `${b.label}: ${b.count} entries, median ${b.median}`);
+
+  boxG.append('g')
+    .attr('transform', `translate(0, ${innerHeight})`)
+    .call(d3.axisBottom(xMonth))
+    .selectAll('text')
+    .attr('transform', 'rotate(-45)')
+    .style('text-anchor', 'end');
+
+  boxG.append('g')
+    .call(d3.axisLeft(ySeverity).ticks(5));
 }
@@ -0,0 +1,77 @@
+export function download_text(filename, mime_type, text) {
+  const blob = new Blob([text], { type: mime_type });
+  const url = URL.createObjectURL(blob);
+  const link = document.createElement('a');
+  link.href = url;
+  link.download = filename;
+  document.body.appendChild(link);
+  link.click();
+  link.remove();
+  URL.revokeObjectURL(url);
+}
+
+// Properties copied from the live chart onto the exported copy, so rules from styles.css survive outside the page
+const INLINED_STYLE_PROPERTIES = [
+  'fill', 'fill-opacity', 'stroke', 'stroke-width', 'stroke-opacity', 'stroke-dasharray', 'opacity',
+  'font-family', 'font-size', 'font-weight', 'font-style', 'text-anchor', 'dominant-baseline', 'visibility',
+];
+
+function serialize_chart(selector) {
+  const svg = document.querySelector(selector);
+  const { width, height } = svg.getBoundingClientRect();
+  const clone = svg.cloneNode(true);
+
+  const originals = [svg, ...svg.querySelectorAll('*')];
+  const copies = [clone, ...clone.querySelectorAll('*')];
+  originals.forEach((original, i) => {
+    const computed = window.getComputedStyle(original);
+    const style = INLINED_STYLE_PROPERTIES
+      .map(property => `${property}:${computed.getPropertyValue(property)}`)
+      .join(';');
+    copies[i].setAttribute('style', `${copies[i].getAttribute('style') || ''};${style}`);
+  });
+
+  // The brush overlay is only useful on the page
+  clone.querySelectorAll('.brush').forEach(brush => brush.remove());
+
+  clone.setAttribute('xmlns', 'http://www.w3.org/2000/svg');
+  clone.setAttribute('width', width);
+  clone.setAttribute('height', height);
+  clone.setAttribute('viewBox', `0 0 ${svg.getAttribute('width')} ${svg.getAttribute('height')}`);
+  return { text: new XMLSerializer().serializeToString(clone), width, height };
+}
+
+export function download_chart_svg(selector, filename) {
+  const { text } = serialize_chart(selector);
+  download_text(filename, 'image/svg+xml', text);
+}
+
+export function download_chart_png(selector, filename) {
+  const scale = 2;
+  const { text, width, height } = serialize_chart(selector);
+  const url = URL.createObjectURL(new Blob([text], { type: 'image/svg+xml' }));
+
+  const image = new Image();
+  image.onload = () => {
+    const canvas = document.createElement('canvas');
+    canvas.width = width * scale;
+    canvas.height = height * scale;
+    const context = canvas.getContext('2d');
+    context.fillStyle = 'white';
+    context.fillRect(0, 0, canvas.width, canvas.height);
+    context.drawImage(image, 0, 0, canvas.width, canvas.height);
+    URL.revokeObjectURL(url);
+
+    canvas.toBlob(blob => {
+      const pngUrl = URL.createObjectURL(blob);
+      const link = document.createElement('a');
+      link.href = pngUrl;
+      link.download = filename;
+      document.body.appendChild(link);
+      link.click();
+      link.remove();
+      URL.revokeObjectURL(pngUrl);
+    }, 'image/png');
+  };
+  image.src = url;
+}
@@ -0,0 +1,43 @@
+pub mod model {
+    pub mod audit;
+    pub mod data_manager;
+    pub mod date_format;
+    pub mod date_map;
+    pub mod keywords;
+    pub mod parser;
+    pub mod query;
+    pub mod search;
+    pub mod tidy;
+    pub mod time_of_day;
+    pub mod report;
+    pub mod schema;
+    pub mod writer;
+    pub mod factors {
+        pub mod factor;
+    }
+    pub mod medications {
+        pub mod medication;
+    }
+    pub mod notes {
+        pub mod note;
+    }
+    pub mod symptoms {
+        pub mod severity_scale;
+        pub mod symptom;
+        pub mod symptom_aliases;
+        pub mod symptom_group;
+        pub mod symptom_id;
+    }
+}
+pub mod view_model {
+    pub mod aggregation;
+    pub mod calendar_heatmap;
+    pub mod chart_config;
+    pub mod chart_kind;
+    pub mod chart_query;
+    pub mod dashboard;
+    pub mod scatter_plot;
+    pub mod severity_distribution;
+    pub mod stacked_area;
+}
+pub mod worker;
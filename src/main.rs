@@ -1,41 +1,65 @@
-mod provider;
+mod storage;
 
-use chrono::{NaiveDateTime, NaiveDate};
+use chrono::{naive::{MAX_DATE, MIN_DATE}, Duration, NaiveDateTime, NaiveDate};
 use gloo_file::{callbacks::FileReader, File};
 use log::{debug, info};
-use model::{data_manager::DataManager};
-use provider::{Provider};
-use view_model::scatter_plot::ScatterPlot;
+use yew_d3_example::model::{data_manager::DataManager, keywords::{KeywordScore, HIGH_SEVERITY_THRESHOLD}, report::{ClinicianReport, DEFAULT_REPORT_WEEKS}, query::EntryQuery, schema::Schema, search::NoteQuery, symptoms::{severity_scale::{SeverityScale, SeverityScales}, symptom_aliases::SymptomAliases, symptom_group::{self, GroupCombination, SymptomGroup}, symptom_id::SymptomId}, tidy, writer};
+use storage::{AliasStore, CsvStore, DashboardStore, GroupStore, ScaleStore};
+use yew_d3_example::view_model::{aggregation::Aggregation, chart_config::ChartConfig, chart_kind::ChartKind, chart_query::{ChartData, ChartError, ChartQuery}, dashboard::{self, Dashboard}};
 use std::{collections::HashMap, rc::Rc};
-use wasm_bindgen::JsValue;
-use web_sys::HtmlInputElement;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{HtmlInputElement, HtmlOptionElement, HtmlSelectElement};
 use yew::prelude::*;
-
-use crate::model::{parser};
+use yew_agent::{Bridge, Bridged};
+use yew_d3_example::worker::{ParseRequest, ParseResponse, ParseWorker};
 
 mod bindings;
-mod model {
-    pub mod data_manager;
-    pub mod date_map;
-    pub mod parser;
-    pub mod time_of_day;
-    pub mod symptoms {
-        pub mod symptom;
-    }
-}
-mod view_model {
-    pub mod scatter_plot;
-}
-
 enum Msg {
-    FetchSymptomScatterplot,
-    SetFetchChartResult(ScatterPlot),
+    FetchChart,
+    DaySelected(String),
+    ChartRangeSelected(String, String),
+    NoteSearchUpdated(Option<String>),
+    EntryFilterUpdated(Option<String>),
+    DayFocused(NaiveDate),
+    FetchKeywords,
+    ExportCsv,
+    ExportTidy(bool),
+    ReportWeeksUpdated(Option<String>),
+    ExportReport,
+    DownloadChartSvg,
+    DownloadChartPng,
+    ClearStoredData,
     ShowError(String),
     Files(Vec<File>),
     Loaded(String, String),
-    SymptomSelectionUpdated(Option<String>),
+    ParseProgress(f32),
+    Parsed(Box<DataManager>, Schema),
+    ParseFailed(String),
+    CancelParse,
+    SymptomSelectionUpdated(Vec<String>),
     StartDateUpdated(Option<String>),
-    EndDateUpdated(Option<String>)
+    EndDateUpdated(Option<String>),
+    ChartKindUpdated(Option<String>),
+    AggregationUpdated(Option<String>),
+    AddChartToDashboard,
+    RemoveDashboardChart(usize),
+    DashboardNameUpdated(Option<String>),
+    SaveDashboard,
+    OpenDashboard(Option<String>),
+    DeleteDashboard,
+    AliasUpdated(Option<String>),
+    AliasNameUpdated(Option<String>),
+    AddAlias,
+    RemoveAlias(String),
+    GroupNameUpdated(Option<String>),
+    GroupCombinationUpdated(Option<String>),
+    SaveGroup,
+    DeleteGroup(String),
+    ScaleMinUpdated(Option<String>),
+    ScaleMaxUpdated(Option<String>),
+    ScaleLabelsUpdated(Option<String>),
+    SaveScale,
+    ResetScale,
 }
 
 struct Model {
@@ -43,52 +67,242 @@ struct Model {
     readers: HashMap<String, FileReader>,
     csv_text: String,
     data_manager: Option<DataManager>,
+    /// Set while an export is being parsed, dropping it cancels the parse
+    parse_worker: Option<Box<dyn Bridge<ParseWorker>>>,
+    parse_progress: f32,
+    /// The columns the loaded export was read from
+    schema: Option<Schema>,
 
-    symptom_names: Vec<String>,
-    selected_symptom: Option<String>,
+    symptom_ids: Vec<SymptomId>,
+    group_ids: Vec<SymptomId>,
+    selected_symptoms: Vec<SymptomId>,
+    /// Names to select once the next export is parsed, e.g. the ones from the URL
+    pending_symptom_names: Vec<String>,
 
     earliest_symptom_date: String,
     latest_symptom_date: String,
 
     selected_start_date: Option<NaiveDate>,
     selected_end_date: Option<NaiveDate>,
+
+    chart_kind: ChartKind,
+    aggregation: Aggregation,
+    /// Only entries matching this `EntryQuery` are charted
+    entry_filter: String,
+    selected_day: Option<NaiveDate>,
+    note_query: NoteQuery,
+    keywords: Vec<KeywordScore>,
+    report_weeks: u32,
+    dashboard: Dashboard,
+    saved_dashboards: Vec<Dashboard>,
+    /// Set when the dashboard's charts changed, they're drawn once the grid's `<svg>`s are rendered
+    redraw_dashboard: bool,
+    aliases: SymptomAliases,
+    new_alias: String,
+    new_alias_name: String,
+    groups: Vec<SymptomGroup>,
+    /// The group being defined, its members are the selected symptoms
+    new_group: SymptomGroup,
+    scales: SeverityScales,
+    /// The scale being edited for the first selected symptom, the labels as typed
+    new_scale: (u8, u8, String),
+    on_day_click: Closure<dyn Fn(String)>,
+    on_chart_brush: Closure<dyn Fn(String, String)>,
 }
 
 static HTML_INPUT_DATE_FORMAT: &str = "%Y-%m-%d";
+/// The chart being edited, drawn into the first cell of the dashboard's grid
+static CHART_ID: &str = "chart";
+/// Days shown either side of a day picked from the note search results or the audit
+const FOCUSED_DAY_CONTEXT_DAYS: i64 = 3;
+const MAX_KEYWORDS_SHOWN: usize = 20;
 
 impl Component for Model {
     type Message = Msg;
     type Properties = ();
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let link = ctx.link().clone();
+        let on_day_click = Closure::wrap(Box::new(move |date: String| link.send_message(Msg::DaySelected(date))) as Box<dyn Fn(String)>);
+        let link = ctx.link().clone();
+        let on_chart_brush = Closure::wrap(Box::new(move |start: String, end: String| link.send_message(Msg::ChartRangeSelected(start, end))) as Box<dyn Fn(String, String)>);
+
+        let chart_config = read_chart_config();
+        info!("Starting from {:?}", chart_config);
+
+        let saved_dashboards = DashboardStore::load().unwrap_or_else(|e| {
+            ctx.link().send_message(Msg::ShowError(format!("Couldn't load saved dashboards: {}", e)));
+            Vec::new()
+        });
+
+        let aliases = AliasStore::load().unwrap_or_else(|e| {
+            ctx.link().send_message(Msg::ShowError(format!("Couldn't load symptom aliases: {}", e)));
+            SymptomAliases::default()
+        });
+
+        let groups = GroupStore::load().unwrap_or_else(|e| {
+            ctx.link().send_message(Msg::ShowError(format!("Couldn't load symptom groups: {}", e)));
+            Vec::new()
+        });
+
+        let scales = ScaleStore::load().unwrap_or_else(|e| {
+            ctx.link().send_message(Msg::ShowError(format!("Couldn't load severity scales: {}", e)));
+            SeverityScales::default()
+        });
+
+        if let Some((csv_name, csv_text)) = CsvStore::load() {
+            info!("Restoring {} from local storage", csv_name);
+            ctx.link().send_message(Msg::Loaded(csv_name, csv_text));
+        }
+
         Self {
             error_msg: String::new(),
             readers: HashMap::default(),
             csv_text: String::new(),
             data_manager: None,
-            symptom_names: Vec::new(),
-            selected_symptom: None,
+            parse_worker: None,
+            parse_progress: 0.0,
+            schema: None,
+            symptom_ids: Vec::new(),
+            group_ids: Vec::new(),
+            selected_symptoms: Vec::new(),
+            pending_symptom_names: chart_config.symptoms,
             earliest_symptom_date: String::new(),
             latest_symptom_date: String::new(),
-            selected_start_date: None,
-            selected_end_date: None,
+            selected_start_date: chart_config.start_date,
+            selected_end_date: chart_config.end_date,
+            chart_kind: chart_config.chart_kind,
+            aggregation: chart_config.aggregation,
+            entry_filter: chart_config.filter,
+            selected_day: None,
+            note_query: NoteQuery::default(),
+            keywords: Vec::new(),
+            report_weeks: DEFAULT_REPORT_WEEKS,
+            dashboard: Dashboard::default(),
+            saved_dashboards,
+            redraw_dashboard: false,
+            aliases,
+            new_alias: String::new(),
+            new_alias_name: String::new(),
+            groups,
+            new_group: SymptomGroup::default(),
+            scales,
+            new_scale: Self::scale_draft(&SeverityScale::default()),
+            on_day_click,
+            on_chart_brush,
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            Msg::FetchSymptomScatterplot => {
-                debug!("Fetching chart...");
-                ctx.link().send_message(
-                    match Provider::fetch_chart(&self.data_manager, &self.selected_symptom, &self.selected_start_date, &self.selected_end_date) {
-                        Some(scatter_plot) => Msg::SetFetchChartResult(scatter_plot),
-                        None => Msg::ShowError("returned null".to_string()),
-                    }
-                );
+            Msg::FetchChart => {
+                debug!("Fetching {:?} chart...", self.chart_kind);
+                if let Err(e) = self.draw_chart(&self.chart_config(), &format!("#{}", CHART_ID), true) {
+                    ctx.link().send_message(Msg::ShowError(e));
+                }
+                true
+            }
+            Msg::ChartRangeSelected(start_date_str, end_date_str) => {
+                self.selected_start_date = parse_html_date(Some(start_date_str));
+                self.selected_end_date = parse_html_date(Some(end_date_str));
+                info!("Zoomed chart to {:?} - {:?}", self.selected_start_date, self.selected_end_date);
+                self.sync_chart_config();
+                ctx.link().send_message(Msg::FetchChart);
+                true
+            }
+            Msg::NoteSearchUpdated(query) => {
+                self.note_query = NoteQuery::parse(query.as_deref().unwrap_or_default());
+                info!("Searching notes for {:?}", self.note_query);
+                true
+            }
+            Msg::EntryFilterUpdated(filter) => {
+                self.entry_filter = filter.unwrap_or_default().trim().to_string();
+                if let Err(e) = EntryQuery::parse(&self.entry_filter) {
+                    ctx.link().send_message(Msg::ShowError(format!("Couldn't read the filter: {}", e)));
+                    return true;
+                }
+                self.sync_chart_config();
+                ctx.link().send_message(Msg::FetchChart);
+                true
+            }
+            Msg::DayFocused(date) => {
+                self.selected_day = Some(date);
+                self.selected_start_date = Some(date - Duration::days(FOCUSED_DAY_CONTEXT_DAYS));
+                self.selected_end_date = Some(date + Duration::days(FOCUSED_DAY_CONTEXT_DAYS));
+                self.sync_chart_config();
+                ctx.link().send_message(Msg::FetchChart);
+                true
+            }
+            Msg::FetchKeywords => {
+                let keywords = self.data_manager.as_ref().and_then(|data_manager| {
+                    let symptom = self.selected_symptoms.first()?;
+                    data_manager.get_symptom_keywords(*symptom, HIGH_SEVERITY_THRESHOLD)
+                });
+                match keywords {
+                    Some(keywords) => self.keywords = keywords,
+                    None => ctx.link().send_message(Msg::ShowError("Select a symptom to find keywords for".to_string())),
+                }
+                true
+            }
+            Msg::ExportCsv => {
+                let data_manager = match &self.data_manager {
+                    Some(data_manager) => data_manager,
+                    None => return false,
+                };
+                let dates = self.selected_start_date.unwrap_or(MIN_DATE)..=self.selected_end_date.unwrap_or(MAX_DATE);
+                match writer::to_bearable_csv_string(data_manager, &self.selected_symptoms, dates) {
+                    Ok(csv_text) => bindings::download_text("bearable-export-filtered.csv", "text/csv", &csv_text),
+                    Err(e) => ctx.link().send_message(Msg::ShowError(format!("Failed to export CSV: {}", e))),
+                }
+                false
+            }
+            Msg::ExportTidy(as_json) => {
+                let data_manager = match &self.data_manager {
+                    Some(data_manager) => data_manager,
+                    None => return false,
+                };
+                let observations = tidy::observations(data_manager);
+                let result = if as_json {
+                    tidy::to_tidy_json_string(&observations).map(|json| ("bearable-tidy.json", "application/json", json)).map_err(|e| e.to_string())
+                } else {
+                    tidy::to_tidy_csv_string(&observations).map(|csv| ("bearable-tidy.csv", "text/csv", csv)).map_err(|e| e.to_string())
+                };
+                match result {
+                    Ok((filename, mime_type, text)) => bindings::download_text(filename, mime_type, &text),
+                    Err(e) => ctx.link().send_message(Msg::ShowError(format!("Failed to export tidy data: {}", e))),
+                }
+                false
+            }
+            Msg::ReportWeeksUpdated(weeks) => {
+                self.report_weeks = weeks.and_then(|w| w.parse::<u32>().ok()).filter(|w| *w > 0).unwrap_or(DEFAULT_REPORT_WEEKS);
+                true
+            }
+            Msg::ExportReport => {
+                match self.data_manager.as_ref().and_then(|data_manager| ClinicianReport::build(data_manager, self.report_weeks)) {
+                    Some(report) => bindings::download_text("clinician-report.html", "text/html", &report.to_html()),
+                    None => ctx.link().send_message(Msg::ShowError("No symptoms to report on".to_string())),
+                }
+                false
+            }
+            Msg::DownloadChartSvg => {
+                bindings::download_chart_svg(&format!("#{}", CHART_ID), &format!("{}-chart.svg", self.chart_kind.id()));
+                false
+            }
+            Msg::DownloadChartPng => {
+                bindings::download_chart_png(&format!("#{}", CHART_ID), &format!("{}-chart.png", self.chart_kind.id()));
+                false
+            }
+            Msg::ClearStoredData => {
+                if let Err(e) = CsvStore::clear() {
+                    ctx.link().send_message(Msg::ShowError(format!("Failed to clear stored data: {}", e)));
+                    return false;
+                }
+                self.unload_export();
                 true
             }
-            Msg::SetFetchChartResult(data) => {
-                Self::show_chart(data);
+            Msg::DaySelected(date_str) => {
+                self.selected_day = parse_html_date(Some(date_str));
+                info!("Selected day {:?}", self.selected_day);
                 true
             }
             Msg::ShowError(msg) => {
@@ -116,40 +330,229 @@ impl Component for Model {
             Msg::Loaded(csv_name, csv_text) => {
                 info!("{:?}", csv_text);
                 self.csv_text = csv_text;
-                self.readers.remove(&csv_name);
-                self.data_manager = Some(parser::parse_into_data_manager_str(self.csv_text.as_str())); //TODO: Some async stuff here to avoid hanging?
-
-                if let Some(data_manager) = &self.data_manager {
-                    self.symptom_names = data_manager.get_symptom_names()
-                        .into_iter()
-                        .map(|s| s.to_owned())
-                        .collect::<Vec<String>>();
-                    let selected_symptom = self.symptom_names.first().and_then(|a| Some(a.to_string()));
-                    ctx.link().clone().send_message(Msg::SymptomSelectionUpdated(selected_symptom));
+                // Only freshly uploaded files need storing, restored ones are already there
+                if self.readers.remove(&csv_name).is_some() {
+                    if let Err(e) = CsvStore::save(&csv_name, &self.csv_text) {
+                        ctx.link().send_message(Msg::ShowError(format!("Couldn't keep the data for next time: {}", e)));
+                    }
                 }
-
+                self.start_parse(ctx);
                 true
             },
-            Msg::SymptomSelectionUpdated(symptom) => {
-                info!("Received symptom selection {:?}", symptom);
-                self.selected_symptom = symptom;
-                if let (Some(selection), Some(data_manager)) = (self.selected_symptom.to_owned(), &self.data_manager) {
-                    let range = data_manager.get_symptom_date_range(selection.as_str()).expect("Symptom has dates");
+            Msg::ParseProgress(progress) => {
+                self.parse_progress = progress;
+                true
+            }
+            Msg::Parsed(mut data_manager, schema) => {
+                self.parse_worker = None;
+                info!("Read the export as schema {:?}", schema);
+                self.schema = Some(schema);
+                data_manager.set_groups(&self.groups);
+                self.symptom_ids = data_manager.get_symptom_ids();
+                self.group_ids = data_manager.get_group_ids();
+
+                // Keep a selection that came in through the URL, as long as this export has those symptoms
+                let mut selected_symptoms = data_manager.get_symptom_ids_for(&std::mem::take(&mut self.pending_symptom_names));
+                if selected_symptoms.is_empty() {
+                    selected_symptoms = self.symptom_ids.first().into_iter().copied().collect();
+                }
+                ctx.link().send_message(Msg::SymptomSelectionUpdated(data_manager.get_symptom_names(&selected_symptoms)));
+                self.data_manager = Some(*data_manager);
+                self.redraw_dashboard = true;
+                true
+            }
+            Msg::ParseFailed(e) => {
+                self.parse_worker = None;
+                ctx.link().send_message(Msg::ShowError(format!("Couldn't read the export: {}", e)));
+                true
+            }
+            Msg::CancelParse => {
+                info!("Cancelled parsing");
+                self.parse_worker = None;
+                true
+            }
+            Msg::SymptomSelectionUpdated(symptoms) => {
+                info!("Received symptom selection {:?}", symptoms);
+                self.selected_symptoms = self.data_manager.as_ref().map(|d| d.get_symptom_ids_for(&symptoms)).unwrap_or_default();
+                if let Some(range) = self.data_manager.as_ref().and_then(|d| d.get_symptoms_date_range(&self.selected_symptoms)) {
                     self.earliest_symptom_date = format_date_for_html(range.start());
                     self.latest_symptom_date = format_date_for_html(range.end());
                 }
+                if let (Some(data_manager), Some(symptom)) = (&self.data_manager, self.selected_symptoms.first()) {
+                    self.new_scale = Self::scale_draft(&data_manager.get_severity_scale(*symptom));
+                }
+                self.sync_chart_config();
                 true
             }
             Msg::StartDateUpdated(start_date_str) => {
                 self.selected_start_date = parse_html_date(start_date_str);
                 info!("Parsed updated start date {:?}", self.selected_start_date);
+                self.sync_chart_config();
                 true
             },
             Msg::EndDateUpdated(end_date) => {
                 self.selected_end_date = parse_html_date(end_date);
                 info!("Parsed updated end date {:?}", self.selected_end_date);
+                self.sync_chart_config();
+                true
+            },
+            Msg::ChartKindUpdated(chart_kind) => {
+                self.chart_kind = chart_kind.as_deref().and_then(ChartKind::from_id).unwrap_or(ChartKind::Scatter);
+                info!("Chart kind updated to {:?}", self.chart_kind);
+                self.sync_chart_config();
                 true
             },
+            Msg::AggregationUpdated(aggregation) => {
+                self.aggregation = aggregation.as_deref().and_then(Aggregation::from_id).unwrap_or(Aggregation::None);
+                info!("Aggregation updated to {:?}", self.aggregation);
+                self.sync_chart_config();
+                true
+            },
+            Msg::AddChartToDashboard => {
+                self.dashboard.charts.push(self.chart_config());
+                self.redraw_dashboard = true;
+                true
+            }
+            Msg::RemoveDashboardChart(index) => {
+                if index < self.dashboard.charts.len() {
+                    self.dashboard.charts.remove(index);
+                    self.redraw_dashboard = true;
+                }
+                true
+            }
+            Msg::DashboardNameUpdated(name) => {
+                self.dashboard.name = name.unwrap_or_default().trim().to_string();
+                true
+            }
+            Msg::SaveDashboard => {
+                if self.dashboard.name.is_empty() {
+                    ctx.link().send_message(Msg::ShowError("Name the dashboard before saving it".to_string()));
+                    return false;
+                }
+                dashboard::save_dashboard(&mut self.saved_dashboards, self.dashboard.clone());
+                if let Err(e) = DashboardStore::save(&self.saved_dashboards) {
+                    ctx.link().send_message(Msg::ShowError(format!("Failed to save dashboard: {}", e)));
+                }
+                true
+            }
+            Msg::OpenDashboard(name) => {
+                self.dashboard = self.saved_dashboards.iter()
+                    .find(|d| Some(&d.name) == name.as_ref())
+                    .cloned()
+                    .unwrap_or_default();
+                info!("Opened dashboard {:?}", self.dashboard.name);
+                self.redraw_dashboard = true;
+                true
+            }
+            Msg::DeleteDashboard => {
+                let deleted = std::mem::take(&mut self.dashboard);
+                self.saved_dashboards.retain(|d| d.name != deleted.name);
+                if let Err(e) = DashboardStore::save(&self.saved_dashboards) {
+                    ctx.link().send_message(Msg::ShowError(format!("Failed to delete dashboard: {}", e)));
+                }
+                true
+            }
+            Msg::AliasUpdated(alias) => {
+                self.new_alias = alias.unwrap_or_default();
+                false
+            }
+            Msg::AliasNameUpdated(name) => {
+                self.new_alias_name = name.unwrap_or_default();
+                false
+            }
+            Msg::AddAlias => {
+                if let Err(e) = self.aliases.set(&self.new_alias, &self.new_alias_name) {
+                    ctx.link().send_message(Msg::ShowError(e));
+                    return false;
+                }
+                info!("Rolling {:?} up into {:?}", self.new_alias, self.new_alias_name);
+                self.new_alias.clear();
+                self.new_alias_name.clear();
+                self.aliases_changed(ctx);
+                true
+            }
+            Msg::RemoveAlias(alias) => {
+                self.aliases.remove(&alias);
+                self.aliases_changed(ctx);
+                true
+            }
+            Msg::GroupNameUpdated(name) => {
+                self.new_group.name = name.unwrap_or_default().trim().to_string();
+                false
+            }
+            Msg::GroupCombinationUpdated(combination) => {
+                self.new_group.combination = combination.as_deref().and_then(GroupCombination::from_id).unwrap_or_default();
+                false
+            }
+            Msg::SaveGroup => {
+                let data_manager = match &self.data_manager {
+                    Some(data_manager) => data_manager,
+                    None => return false,
+                };
+                let members = self.selected_symptoms.iter()
+                    .filter(|id| !data_manager.is_group(**id))
+                    .map(|id| data_manager.get_symptom_name(*id).to_string())
+                    .collect::<Vec<String>>();
+                let error = if self.new_group.name.is_empty() {
+                    Some("Name the group before saving it".to_string())
+                } else if members.is_empty() {
+                    Some("Select the symptoms that make up the group".to_string())
+                } else if data_manager.get_symptom_id(&self.new_group.name).is_some_and(|id| !data_manager.is_group(id)) {
+                    Some(format!("There's already a symptom called \"{}\"", self.new_group.name))
+                } else {
+                    None
+                };
+                if let Some(error) = error {
+                    ctx.link().send_message(Msg::ShowError(error));
+                    return false;
+                }
+                let group = SymptomGroup { members, ..self.new_group.clone() };
+                info!("Saving group {:?}", group);
+                symptom_group::save_group(&mut self.groups, group);
+                self.groups_changed(ctx);
+                true
+            }
+            Msg::DeleteGroup(name) => {
+                self.groups.retain(|g| g.name != name);
+                self.groups_changed(ctx);
+                true
+            }
+            Msg::ScaleMinUpdated(min) => {
+                self.new_scale.0 = min.and_then(|m| m.parse::<u8>().ok()).unwrap_or_default();
+                false
+            }
+            Msg::ScaleMaxUpdated(max) => {
+                self.new_scale.1 = max.and_then(|m| m.parse::<u8>().ok()).unwrap_or_default();
+                false
+            }
+            Msg::ScaleLabelsUpdated(labels) => {
+                self.new_scale.2 = labels.unwrap_or_default();
+                false
+            }
+            Msg::SaveScale => {
+                let (min, max, labels) = &self.new_scale;
+                let scale = match SeverityScale::with_labels(*min, *max, labels) {
+                    Ok(scale) => scale,
+                    Err(e) => {
+                        ctx.link().send_message(Msg::ShowError(e));
+                        return false;
+                    }
+                };
+                match self.first_selected_name() {
+                    Some(name) => self.scales.set(&name, scale),
+                    None => return false,
+                }
+                self.scales_changed(ctx);
+                true
+            }
+            Msg::ResetScale => {
+                match self.first_selected_name() {
+                    Some(name) => self.scales.remove(&name),
+                    None => return false,
+                }
+                self.scales_changed(ctx);
+                true
+            }
         }
     }
 
@@ -157,34 +560,187 @@ impl Component for Model {
         html! {
             <div>
                 <input type="file" multiple=false accept=".csv" onchange={ctx.link().callback(move |e| Self::on_file_change(e))} />
+                <button onclick={ctx.link().callback(|_| Msg::ClearStoredData)}>{ "Clear stored data" }</button>
 
-                <select name="symptom_choice" id="symptom_choice" onchange={ctx.link().callback(move |e| Self::on_symptom_change(e))}>
-                    { for self.symptom_names.iter().map(|e| self.view_option(e)) }
+                <select name="symptom_choice" id="symptom_choice" multiple=true onchange={ctx.link().callback(move |e| Self::on_symptom_change(e))}>
+                    if !self.group_ids.is_empty() {
+                        <optgroup label="Groups">
+                            { for self.group_ids.iter().map(|id| self.view_option(*id)) }
+                        </optgroup>
+                    }
+                    <optgroup label="Symptoms">
+                        { for self.symptom_ids.iter().map(|id| self.view_option(*id)) }
+                    </optgroup>
                 </select>
 
-                <input type="date" id="start_date" name="start_date" 
+                <input type="date" id="start_date" name="start_date"
+                    value={self.selected_start_date.map(format_date_for_html_input).unwrap_or_default()}
                     min={self.earliest_symptom_date.to_owned()}
                     max={self.latest_symptom_date.to_owned()}
                     onchange={ctx.link().callback(move |e| Self::on_start_date_change(e))}/>
                 <input type="date" id="end_date" name="end_date"
+                    value={self.selected_end_date.map(format_date_for_html_input).unwrap_or_default()}
                     min={self.earliest_symptom_date.to_owned()}
                     max={self.latest_symptom_date.to_owned()}
                     onchange={ctx.link().callback(move |e| Self::on_end_date_change(e))}/>
 
-                <button onclick={ctx.link().callback(|_| Msg::FetchSymptomScatterplot)}>{ "Fetch" }</button>
+                <select name="chart_kind" id="chart_kind" onchange={ctx.link().callback(Self::on_chart_kind_change)}>
+                    { for ChartKind::ALL.iter().map(|kind| self.view_chart_kind_option(kind)) }
+                </select>
+
+                <select name="aggregation" id="aggregation" onchange={ctx.link().callback(Self::on_aggregation_change)}>
+                    { for Aggregation::ALL.iter().map(|aggregation| self.view_aggregation_option(aggregation)) }
+                </select>
+
+                <input type="search" id="entry_filter" name="entry_filter" size="40" value={self.entry_filter.to_owned()}
+                    placeholder="Filter entries, e.g. severity >= 3 and weekday in [Sat, Sun] and factor:alcohol"
+                    onchange={ctx.link().callback(|e| Msg::EntryFilterUpdated(get_html_input_value(e)))}/>
+
+                <button onclick={ctx.link().callback(|_| Msg::FetchChart)}>{ "Fetch" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::DownloadChartSvg)}>{ "Download SVG" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::DownloadChartPng)}>{ "Download PNG" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::ExportCsv)}>{ "Export CSV" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::ExportTidy(false))}>{ "Export tidy CSV" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::ExportTidy(true))}>{ "Export tidy JSON" }</button>
+
+                <input type="number" id="report_weeks" name="report_weeks" min="1" value={self.report_weeks.to_string()}
+                    onchange={ctx.link().callback(Self::on_report_weeks_change)}/>
+                <button onclick={ctx.link().callback(|_| Msg::ExportReport)}>{ "Clinician report" }</button>
+                { self.view_parse_progress(ctx) }
+                { self.view_schema() }
                 <p style="color: red;"> { self.error_msg.clone() }</p>
-                <svg id="chart" width="960" height="500"></svg>
+                { self.view_dashboard(ctx) }
+                { self.view_day_log() }
+
+                <input type="search" id="note_search" name="note_search" placeholder="Search notes"
+                    onchange={ctx.link().callback(Self::on_note_search_change)}/>
+                { self.view_note_results(ctx) }
+
+                <button onclick={ctx.link().callback(|_| Msg::FetchKeywords)}>{ "Keywords on bad days" }</button>
+                { self.view_keywords() }
+
+                { self.view_audit(ctx) }
+
+                { self.view_aliases(ctx) }
+                { self.view_groups(ctx) }
+                { self.view_scale(ctx) }
             </div>
         }
     }
+
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        if self.data_manager.is_none() || !std::mem::take(&mut self.redraw_dashboard) {
+            return;
+        }
+        for (index, config) in self.dashboard.charts.iter().enumerate() {
+            if let Err(e) = self.draw_chart(config, &format!("#{}", Dashboard::chart_id(index)), false) {
+                ctx.link().send_message(Msg::ShowError(e));
+            }
+        }
+    }
 }
 
 impl Model {
-    fn show_chart(scatter_plot: ScatterPlot) {
-        debug!("Showing chart");
-        // call js
-        // the bindings are defined in bindings.rs
-        bindings::show_chart(JsValue::from_serde(&scatter_plot.points).unwrap());
+    /// Parses `csv_text` in the worker, replacing any parse that's still running
+    fn start_parse(&mut self, ctx: &Context<Self>) {
+        let mut parse_worker = ParseWorker::bridge(ctx.link().callback(|response| match response {
+            ParseResponse::Progress(progress) => Msg::ParseProgress(progress),
+            ParseResponse::Parsed(data_manager, schema) => Msg::Parsed(data_manager, schema),
+            ParseResponse::Failed(e) => Msg::ParseFailed(e),
+        }));
+        parse_worker.send(ParseRequest { csv_text: self.csv_text.to_owned(), aliases: self.aliases.clone(), scales: self.scales.clone() });
+        self.parse_worker = Some(parse_worker);
+        self.parse_progress = 0.0;
+        // Ids only mean something to the export they came from, so carry the selection over by name
+        if let Some(data_manager) = &self.data_manager {
+            self.pending_symptom_names = data_manager.get_symptom_names(&self.selected_symptoms);
+        }
+    }
+
+    /// Forgets the loaded export and empties its charts, the settings and dashboards stay
+    fn unload_export(&mut self) {
+        self.csv_text.clear();
+        self.data_manager = None;
+        self.parse_worker = None;
+        self.schema = None;
+        self.symptom_ids.clear();
+        self.group_ids.clear();
+        self.selected_symptoms.clear();
+        self.selected_day = None;
+        self.keywords.clear();
+        self.error_msg.clear();
+        bindings::clear_chart(&format!("#{}", CHART_ID));
+        for index in 0..self.dashboard.charts.len() {
+            bindings::clear_chart(&format!("#{}", Dashboard::chart_id(index)));
+        }
+    }
+
+    /// Groups are combined from the parsed entries, so they're set again without parsing
+    fn groups_changed(&mut self, ctx: &Context<Self>) {
+        if let Err(e) = GroupStore::save(&self.groups) {
+            ctx.link().send_message(Msg::ShowError(format!("Failed to save symptom groups: {}", e)));
+        }
+        if let Some(data_manager) = &mut self.data_manager {
+            // Group ids change when the groups are set again, so keep the selection by name
+            let selected_names = data_manager.get_symptom_names(&self.selected_symptoms);
+            data_manager.set_groups(&self.groups);
+            self.group_ids = data_manager.get_group_ids();
+            self.selected_symptoms = data_manager.get_symptom_ids_for(&selected_names);
+            self.redraw_dashboard = true;
+        }
+    }
+
+    /// A scale's labels decide which symptom a labelled detail is read as, so like aliases it needs a parse
+    fn scales_changed(&mut self, ctx: &Context<Self>) {
+        if let Err(e) = ScaleStore::save(&self.scales) {
+            ctx.link().send_message(Msg::ShowError(format!("Failed to save severity scales: {}", e)));
+        }
+        if !self.csv_text.is_empty() {
+            self.start_parse(ctx);
+        }
+    }
+
+    fn scale_draft(scale: &SeverityScale) -> (u8, u8, String) {
+        (scale.min, scale.max, scale.labels_text())
+    }
+
+    fn first_selected_name(&self) -> Option<String> {
+        let data_manager = self.data_manager.as_ref()?;
+        Some(data_manager.get_symptom_name(*self.selected_symptoms.first()?).to_string())
+    }
+
+    /// Aliases are applied while parsing, so the loaded export is parsed again to pick them up
+    fn aliases_changed(&mut self, ctx: &Context<Self>) {
+        if let Err(e) = AliasStore::save(&self.aliases) {
+            ctx.link().send_message(Msg::ShowError(format!("Failed to save symptom aliases: {}", e)));
+        }
+        if !self.csv_text.is_empty() {
+            self.start_parse(ctx);
+        }
+    }
+
+    /// Draws the chart into the `<svg>` matching `selector`, only a brushable chart can be dragged across to zoom
+    fn draw_chart(&self, config: &ChartConfig, selector: &str, brushable: bool) -> Result<(), String> {
+        let chart = self.data_manager.as_ref()
+            .ok_or(ChartError::NoExport)
+            .and_then(|data_manager| ChartQuery::from_config(config, data_manager)?.run(data_manager))
+            .map_err(|e| format!("Can't show {}: {}", config.description(), e))?;
+        match chart {
+            ChartData::Scatter(scatter_plot) => {
+                let on_brush = if brushable { Some(self.on_chart_brush.as_ref().unchecked_ref()) } else { None };
+                bindings::show_chart(selector, JsValue::from_serde(&scatter_plot).unwrap(), on_brush);
+            }
+            ChartData::CalendarHeatmap(heatmap) => {
+                bindings::show_calendar_heatmap(selector, JsValue::from_serde(&heatmap).unwrap(), self.on_day_click.as_ref().unchecked_ref());
+            }
+            ChartData::SymptomBurden(burden) => {
+                bindings::show_stacked_area(selector, JsValue::from_serde(&burden).unwrap());
+            }
+            ChartData::SeverityDistribution(distribution) => {
+                bindings::show_severity_distribution(selector, JsValue::from_serde(&distribution).unwrap());
+            }
+        }
+        Ok(())
     }
 
     fn on_file_change(e: Event) -> Msg {
@@ -205,9 +761,15 @@ impl Model {
 
     fn on_symptom_change(e: Event) -> Msg {
         info!("On symptom change");
-        let value = get_html_input_value(e);
+        let select: HtmlSelectElement = e.target_unchecked_into();
+        let options = select.selected_options();
+        let values = (0..options.length())
+            .filter_map(|i| options.item(i))
+            .filter_map(|element| element.dyn_into::<HtmlOptionElement>().ok())
+            .map(|option| option.value())
+            .collect::<Vec<String>>();
 
-        Msg::SymptomSelectionUpdated(value)
+        Msg::SymptomSelectionUpdated(values)
     }
 
     fn on_start_date_change(e: Event) -> Msg {
@@ -223,14 +785,344 @@ impl Model {
         Msg::EndDateUpdated(value)
     }
 
-    fn view_option(&self, symptom: &str) -> Html {
-        let owned_symptom = symptom.to_string();
+    fn on_chart_kind_change(e: Event) -> Msg {
+        info!("On chart kind change");
+        let value = get_html_input_value(e);
+        Msg::ChartKindUpdated(value)
+    }
+
+    fn on_aggregation_change(e: Event) -> Msg {
+        info!("On aggregation change");
+        let value = get_html_input_value(e);
+        Msg::AggregationUpdated(value)
+    }
+
+    fn view_aggregation_option(&self, aggregation: &Aggregation) -> Html {
+        html! {
+            <option value={aggregation.id()} selected={&self.aggregation == aggregation}>{ aggregation.label() }</option>
+        }
+    }
+
+    fn chart_config(&self) -> ChartConfig {
+        ChartConfig {
+            chart_kind: self.chart_kind,
+            symptoms: match &self.data_manager {
+                Some(data_manager) => data_manager.get_symptom_names(&self.selected_symptoms),
+                None => self.pending_symptom_names.to_owned(),
+            },
+            start_date: self.selected_start_date,
+            end_date: self.selected_end_date,
+            aggregation: self.aggregation,
+            filter: self.entry_filter.to_owned(),
+        }
+    }
+
+    fn sync_chart_config(&self) {
+        let chart_config = self.chart_config();
+        let history = web_sys::window().and_then(|window| window.history().ok());
+        if let Some(history) = history {
+            if let Err(e) = history.replace_state_with_url(&JsValue::NULL, "", Some(&chart_config.to_fragment())) {
+                info!("Failed to update the URL: {:?}", e);
+            }
+        }
+    }
+
+    fn view_chart_kind_option(&self, chart_kind: &ChartKind) -> Html {
+        html! {
+            <option value={chart_kind.id()} selected={&self.chart_kind == chart_kind}>{ chart_kind.label() }</option>
+        }
+    }
+
+    fn view_parse_progress(&self, ctx: &Context<Self>) -> Html {
+        if self.parse_worker.is_none() {
+            return html! {};
+        }
+        html! {
+            <div id="parse_progress">
+                <progress max="1" value={self.parse_progress.to_string()}></progress>
+                { format!(" Parsing... {:.0}% ", self.parse_progress * 100.0) }
+                <button onclick={ctx.link().callback(|_| Msg::CancelParse)}>{ "Cancel" }</button>
+            </div>
+        }
+    }
+
+    fn view_day_log(&self) -> Html {
+        let (day, data_manager) = match (self.selected_day, &self.data_manager) {
+            (Some(day), Some(data_manager)) => (day, data_manager),
+            _ => return html! {},
+        };
+        html! {
+            <table id="day_log">
+                <caption>{ day.format("%A %-d %B %Y").to_string() }</caption>
+                <tr><th>{ "Time of day" }</th><th>{ "Symptom" }</th><th>{ "Severity" }</th></tr>
+                { for data_manager.get_day_log(day).into_iter().map(|symptom| html! {
+                    <tr>
+                        <td>{ format!("{:?}", symptom.time_of_day) }</td>
+                        <td>{ data_manager.get_symptom_name(symptom.id) }</td>
+                        <td>{ symptom.severity }</td>
+                    </tr>
+                }) }
+            </table>
+        }
+    }
+
+    fn view_schema(&self) -> Html {
+        let schema = match &self.schema {
+            Some(schema) => schema,
+            None => return html! {},
+        };
+        let missing_columns = schema.missing_columns();
+        html! {
+            <p id="schema">
+                { format!("Bearable export schema {}", schema.version.label()) }
+                if !missing_columns.is_empty() {
+                    { format!(", without the {} columns", missing_columns.join(", ")) }
+                }
+            </p>
+        }
+    }
+
+    fn on_note_search_change(e: Event) -> Msg {
+        info!("On note search change");
+        let value = get_html_input_value(e);
+        Msg::NoteSearchUpdated(value)
+    }
+
+    fn view_note_results(&self, ctx: &Context<Self>) -> Html {
+        let data_manager = match &self.data_manager {
+            Some(data_manager) if !self.note_query.is_empty() => data_manager,
+            _ => return html! {},
+        };
         html! {
-            <option value={owned_symptom}>{ symptom }</option>
+            <ul id="note_results">
+                { for data_manager.search_notes(&self.note_query).into_iter().map(|note| {
+                    let date = note.date;
+                    html! {
+                        <li onclick={ctx.link().callback(move |_| Msg::DayFocused(date))}>
+                            <strong>{ format_date_for_html_input(note.date) }</strong>
+                            { format!(" {} {}: {}", note.category, note.detail, note.notes) }
+                        </li>
+                    }
+                }) }
+            </ul>
+        }
+    }
+
+    fn on_report_weeks_change(e: Event) -> Msg {
+        let value = get_html_input_value(e);
+        Msg::ReportWeeksUpdated(value)
+    }
+
+    fn view_keywords(&self) -> Html {
+        if self.keywords.is_empty() {
+            return html! {};
+        }
+        html! {
+            <table id="keywords">
+                <tr><th>{ "Term" }</th><th>{ "Bad days" }</th><th>{ "Other days" }</th><th>{ "Lift" }</th></tr>
+                { for self.keywords.iter().take(MAX_KEYWORDS_SHOWN).map(|keyword| html! {
+                    <tr>
+                        <td>{ keyword.term.to_owned() }</td>
+                        <td>{ keyword.high_days }</td>
+                        <td>{ keyword.other_days }</td>
+                        <td>{ format!("{:.2}", keyword.lift) }</td>
+                    </tr>
+                }) }
+            </table>
+        }
+    }
+
+    fn view_audit(&self, ctx: &Context<Self>) -> Html {
+        let issues = match &self.data_manager {
+            Some(data_manager) => data_manager.get_audit_issues(),
+            None => return html! {},
+        };
+        html! {
+            <details id="audit">
+                <summary>{ format!("Data quality: {} issues", issues.len()) }</summary>
+                <table>
+                    <tr><th>{ "Date" }</th><th>{ "Issue" }</th><th>{ "Details" }</th></tr>
+                    { for issues.iter().map(|issue| {
+                        let date = issue.date();
+                        html! {
+                            <tr onclick={ctx.link().callback(move |_| Msg::DayFocused(date))}>
+                                <td>{ format_date_for_html_input(date) }</td>
+                                <td>{ issue.label() }</td>
+                                <td>{ issue.description() }</td>
+                            </tr>
+                        }
+                    }) }
+                </table>
+            </details>
+        }
+    }
+
+    fn on_alias_change(e: Event) -> Msg {
+        let value = get_html_input_value(e);
+        Msg::AliasUpdated(value)
+    }
+
+    fn on_alias_name_change(e: Event) -> Msg {
+        let value = get_html_input_value(e);
+        Msg::AliasNameUpdated(value)
+    }
+
+    fn view_aliases(&self, ctx: &Context<Self>) -> Html {
+        let symptom_names = self.data_manager.as_ref().map(|d| d.get_symptom_names(&self.symptom_ids)).unwrap_or_default();
+        html! {
+            <div id="aliases">
+                <table>
+                    <tr><th>{ "Old name" }</th><th>{ "Rolls up into" }</th><th></th></tr>
+                    { for self.aliases.iter().map(|(alias, name)| {
+                        let owned_alias = alias.to_string();
+                        html! {
+                            <tr>
+                                <td>{ alias }</td>
+                                <td>{ name }</td>
+                                <td><button onclick={ctx.link().callback(move |_| Msg::RemoveAlias(owned_alias.to_owned()))}>{ "Remove" }</button></td>
+                            </tr>
+                        }
+                    }) }
+                </table>
+                <datalist id="symptom_names">
+                    { for symptom_names.into_iter().map(|name| html! { <option value={name}/> }) }
+                </datalist>
+                <input type="text" id="alias" name="alias" placeholder="Old symptom name" list="symptom_names"
+                    value={self.new_alias.to_owned()}
+                    onchange={ctx.link().callback(Self::on_alias_change)}/>
+                <input type="text" id="alias_name" name="alias_name" placeholder="Current symptom name" list="symptom_names"
+                    value={self.new_alias_name.to_owned()}
+                    onchange={ctx.link().callback(Self::on_alias_name_change)}/>
+                <button onclick={ctx.link().callback(|_| Msg::AddAlias)}>{ "Add alias" }</button>
+            </div>
+        }
+    }
+
+    fn on_group_name_change(e: Event) -> Msg {
+        let value = get_html_input_value(e);
+        Msg::GroupNameUpdated(value)
+    }
+
+    fn on_group_combination_change(e: Event) -> Msg {
+        let value = get_html_input_value(e);
+        Msg::GroupCombinationUpdated(value)
+    }
+
+    fn view_groups(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div id="groups">
+                <table>
+                    <tr><th>{ "Group" }</th><th>{ "Symptoms" }</th><th>{ "Combined as" }</th><th></th></tr>
+                    { for self.groups.iter().map(|group| {
+                        let name = group.name.to_owned();
+                        html! {
+                            <tr>
+                                <td>{ group.name.to_owned() }</td>
+                                <td>{ group.members.join(", ") }</td>
+                                <td>{ group.combination.label() }</td>
+                                <td><button onclick={ctx.link().callback(move |_| Msg::DeleteGroup(name.to_owned()))}>{ "Delete" }</button></td>
+                            </tr>
+                        }
+                    }) }
+                </table>
+                <input type="text" id="group_name" name="group_name" placeholder="Group name"
+                    value={self.new_group.name.to_owned()}
+                    onchange={ctx.link().callback(Self::on_group_name_change)}/>
+                <select name="group_combination" id="group_combination" onchange={ctx.link().callback(Self::on_group_combination_change)}>
+                    { for GroupCombination::ALL.iter().map(|combination| html! {
+                        <option value={combination.id()} selected={&self.new_group.combination == combination}>{ combination.label() }</option>
+                    }) }
+                </select>
+                <button onclick={ctx.link().callback(|_| Msg::SaveGroup)}>{ "Save selected symptoms as group" }</button>
+            </div>
+        }
+    }
+
+    fn view_scale(&self, ctx: &Context<Self>) -> Html {
+        let name = match self.first_selected_name() {
+            Some(name) => name,
+            None => return html! {},
+        };
+        let (min, max, labels) = &self.new_scale;
+        html! {
+            <div id="severity_scale">
+                { format!("Severity scale for {}", name) }
+                <input type="number" id="scale_min" name="scale_min" min="0" max="255" value={min.to_string()}
+                    onchange={ctx.link().callback(|e| Msg::ScaleMinUpdated(get_html_input_value(e)))}/>
+                <input type="number" id="scale_max" name="scale_max" min="1" max="255" value={max.to_string()}
+                    onchange={ctx.link().callback(|e| Msg::ScaleMaxUpdated(get_html_input_value(e)))}/>
+                <input type="text" id="scale_labels" name="scale_labels" placeholder="Labels from the lowest value up, e.g. ,Mild,Moderate"
+                    value={labels.to_owned()}
+                    onchange={ctx.link().callback(|e| Msg::ScaleLabelsUpdated(get_html_input_value(e)))}/>
+                <button onclick={ctx.link().callback(|_| Msg::SaveScale)}>{ "Save scale" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::ResetScale)} disabled={self.scales.get(&name).is_none()}>{ "Reset scale" }</button>
+            </div>
+        }
+    }
+
+    fn on_dashboard_name_change(e: Event) -> Msg {
+        let value = get_html_input_value(e);
+        Msg::DashboardNameUpdated(value)
+    }
+
+    fn on_dashboard_change(e: Event) -> Msg {
+        let value = get_html_input_value(e);
+        Msg::OpenDashboard(value)
+    }
+
+    fn view_dashboard(&self, ctx: &Context<Self>) -> Html {
+        let is_saved = self.saved_dashboards.iter().any(|d| d.name == self.dashboard.name);
+        html! {
+            <div id="dashboard">
+                <select name="saved_dashboards" id="saved_dashboards" onchange={ctx.link().callback(Self::on_dashboard_change)}>
+                    <option value="" selected={!is_saved}>{ "New dashboard" }</option>
+                    { for self.saved_dashboards.iter().map(|d| html! {
+                        <option value={d.name.to_owned()} selected={d.name == self.dashboard.name}>{ d.name.to_owned() }</option>
+                    }) }
+                </select>
+                <input type="text" id="dashboard_name" name="dashboard_name" placeholder="Dashboard name"
+                    value={self.dashboard.name.to_owned()}
+                    onchange={ctx.link().callback(Self::on_dashboard_name_change)}/>
+                <button onclick={ctx.link().callback(|_| Msg::AddChartToDashboard)}>{ "Add chart to dashboard" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::SaveDashboard)}>{ "Save dashboard" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::DeleteDashboard)} disabled={!is_saved}>{ "Delete dashboard" }</button>
+
+                <div class="chart-grid">
+                    { Self::view_chart_cell(CHART_ID.to_string(), html! { self.chart_config().description() }) }
+                    { for self.dashboard.charts.iter().enumerate().map(|(index, config)| Self::view_chart_cell(Dashboard::chart_id(index), html! {
+                        <>
+                            { config.description() }
+                            <button onclick={ctx.link().callback(move |_| Msg::RemoveDashboardChart(index))}>{ "Remove" }</button>
+                        </>
+                    })) }
+                </div>
+            </div>
+        }
+    }
+
+    /// Every chart gets the same `<svg>`, scaled down to the grid's cells
+    fn view_chart_cell(id: String, caption: Html) -> Html {
+        html! {
+            <figure>
+                <svg id={id} width="960" height="500" viewBox="0 0 960 500"></svg>
+                <figcaption>{ caption }</figcaption>
+            </figure>
+        }
+    }
+
+    fn view_option(&self, symptom: SymptomId) -> Html {
+        let name = self.data_manager.as_ref().map(|d| d.get_symptom_name(symptom)).unwrap_or_default().to_string();
+        html! {
+            <option value={name.to_owned()} selected={self.selected_symptoms.contains(&symptom)}>{ name }</option>
         }
     }
 }
 
+fn read_chart_config() -> ChartConfig {
+    let fragment = web_sys::window().and_then(|window| window.location().hash().ok()).unwrap_or_default();
+    ChartConfig::parse(&fragment)
+}
+
 fn get_html_input_value(inputOnChangeEvent: Event) -> Option<String> {
     let input: HtmlInputElement = inputOnChangeEvent.target_unchecked_into();
     let value = input.value();
@@ -242,7 +1134,11 @@ fn get_html_input_value(inputOnChangeEvent: Event) -> Option<String> {
 }
 
 fn format_date_for_html(dateTime: &NaiveDateTime) -> String {
-    dateTime.date().format(HTML_INPUT_DATE_FORMAT).to_string()
+    format_date_for_html_input(dateTime.date())
+}
+
+fn format_date_for_html_input(date: NaiveDate) -> String {
+    date.format(HTML_INPUT_DATE_FORMAT).to_string()
 }
 
 fn parse_html_date(html_date_str: Option<String>) -> Option<NaiveDate> {
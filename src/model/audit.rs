@@ -0,0 +1,143 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    parser::CsvRecord,
+    symptoms::{severity_scale::SeverityScale, symptom::{Symptom, SYMPTOM_CATEGORY}},
+    time_of_day::TimeOfDay,
+};
+
+/// Stretches of at least this many days without anything logged are flagged
+const MIN_GAP_DAYS: i64 = 3;
+
+/// Something in an export that looks like a logging mistake rather than how the day went
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditIssue {
+    /// The same thing logged more than once in one time of day, each one is kept and charted
+    DuplicateEntry { date: NaiveDate, time_of_day: TimeOfDay, name: String, count: usize },
+    /// Days where e.g. only symptoms were logged, often a quick check-in that skipped the rest
+    SingleCategoryDay { date: NaiveDate, category: String },
+    /// Nothing logged between the two days
+    LoggingGap { from: NaiveDate, to: NaiveDate },
+    /// A symptom's severity label saying something else than its rating, e.g. "2" with "(Severe)"
+    SeverityMismatch { date: NaiveDate, time_of_day: TimeOfDay, detail: String, amount: String },
+}
+
+impl AuditIssue {
+    /// The day the issue starts on, issues are sorted by it
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            AuditIssue::DuplicateEntry { date, .. }
+            | AuditIssue::SingleCategoryDay { date, .. }
+            | AuditIssue::SeverityMismatch { date, .. } => *date,
+            AuditIssue::LoggingGap { from, .. } => *from,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuditIssue::DuplicateEntry { .. } => "Duplicate entry",
+            AuditIssue::SingleCategoryDay { .. } => "Single category day",
+            AuditIssue::LoggingGap { .. } => "Logging gap",
+            AuditIssue::SeverityMismatch { .. } => "Severity mismatch",
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            AuditIssue::DuplicateEntry { time_of_day, name, count, .. } =>
+                format!("{} logged {} times in {}", name, count, serde_plain::to_string(time_of_day).unwrap_or_default()),
+            AuditIssue::SingleCategoryDay { category, .. } => format!("Only {} logged", category),
+            AuditIssue::LoggingGap { from, to } => format!("Nothing logged for {} days until {}", (*to - *from).num_days() - 1, to),
+            AuditIssue::SeverityMismatch { detail, amount, .. } => format!("{} rated {}", detail, amount),
+        }
+    }
+}
+
+/// Collects the issues one row at a time, alongside `DataManagerBuilder`
+#[derive(Default)]
+pub struct AuditBuilder {
+    /// How often each name was logged per category and time of day
+    slots: BTreeMap<(NaiveDate, TimeOfDay, String, String), usize>,
+    categories: BTreeMap<NaiveDate, BTreeSet<String>>,
+    mismatches: Vec<AuditIssue>,
+}
+
+impl AuditBuilder {
+    /// `name` is what the row logs, for symptoms the name the severity label and aliases were resolved away from
+    pub fn add_row(&mut self, row: &CsvRecord, name: &str) {
+        let time_of_day = serde_plain::from_str::<TimeOfDay>(row.time_of_day).unwrap_or(TimeOfDay::None);
+        *self.slots.entry((row.date, time_of_day, row.category.to_string(), name.to_string())).or_default() += 1;
+        self.categories.entry(row.date).or_default().insert(row.category.to_string());
+
+        if row.category == SYMPTOM_CATEGORY && !Self::severity_matches(row) {
+            self.mismatches.push(AuditIssue::SeverityMismatch {
+                date: row.date,
+                time_of_day,
+                detail: row.detail.to_string(),
+                amount: row.amount.to_string(),
+            });
+        }
+    }
+
+    /// Details without a label, or with a label we can't tell the value of, can't disagree
+    fn severity_matches(row: &CsvRecord) -> bool {
+        let label = match Symptom::parse_severity_label(row.detail) {
+            Some(label) => label,
+            None => return true,
+        };
+        let expected = label.parse::<u8>().ok().or_else(|| SeverityScale::default().value(label));
+        expected.is_none() || expected == row.amount.trim().parse::<u8>().ok()
+    }
+
+    pub fn build(self) -> Vec<AuditIssue> {
+        let mut issues = self.slots.into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|((date, time_of_day, _, name), count)| AuditIssue::DuplicateEntry { date, time_of_day, name, count })
+            .collect::<Vec<AuditIssue>>();
+        issues.extend(self.categories.iter()
+            .filter(|(_, categories)| categories.len() == 1)
+            .map(|(date, categories)| AuditIssue::SingleCategoryDay { date: *date, category: categories.iter().next().unwrap().to_owned() }));
+        let dates = self.categories.keys().collect::<Vec<&NaiveDate>>();
+        issues.extend(dates.windows(2)
+            .filter(|days| (*days[1] - *days[0]).num_days() > MIN_GAP_DAYS)
+            .map(|days| AuditIssue::LoggingGap { from: *days[0], to: *days[1] }));
+        issues.extend(self.mismatches);
+        issues.sort_by_key(AuditIssue::date);
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::parser::parse_into_data_manager_str;
+
+    use super::*;
+
+    #[test]
+    fn GetAuditIssues_ForSuspiciousExport_FlagsEachKindOfIssue() {
+        let text = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"5th Jan 2022","Wednesday","am","Symptom","1","Neck pain (Mild)",""
+"5th Jan 2022","Wednesday","am","Symptom","3","Neck pain (Severe)",""
+"5th Jan 2022","Wednesday","pm","Symptom","2","Headache (Severe)",""
+"5th Jan 2022","Wednesday","pm","Mood","3","Mood",""
+"6th Jan 2022","Thursday","am","Symptom","1","Neck pain (Mild)",""
+"6th Jan 2022","Thursday","am","Mood","4","Mood",""
+"11th Jan 2022","Tuesday","am","Mood","4","Mood",""
+"12th Jan 2022","Wednesday","am","Symptom","2","Neck pain (Moderate)",""
+"12th Jan 2022","Wednesday","am","Mood","4","Mood","""#;
+        let date = |day| NaiveDate::from_ymd(2022, 1, day);
+
+        let issues = parse_into_data_manager_str(text).unwrap().get_audit_issues().to_vec();
+
+        assert_eq!(issues, vec![
+            AuditIssue::DuplicateEntry { date: date(5), time_of_day: TimeOfDay::AM, name: "Neck pain".to_string(), count: 2 },
+            AuditIssue::SeverityMismatch { date: date(5), time_of_day: TimeOfDay::PM, detail: "Headache (Severe)".to_string(), amount: "2".to_string() },
+            AuditIssue::LoggingGap { from: date(6), to: date(11) },
+            AuditIssue::SingleCategoryDay { date: date(11), category: "Mood".to_string() },
+        ]);
+        assert_eq!(issues[2].description(), "Nothing logged for 4 days until 2022-01-11");
+    }
+}
@@ -1,101 +1,728 @@
-use std::{collections::HashMap, iter::FromIterator, ops::{RangeBounds, RangeInclusive}};
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, ops::{RangeBounds, RangeInclusive}};
 
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use log::debug;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::view_model::scatter_plot::{ScatterPlot, DateTimeValuePoint};
+use crate::view_model::{
+    calendar_heatmap::{CalendarHeatmap, DateValuePoint},
+    chart_query::Reducer,
+    scatter_plot::{DateTimeValuePoint, ScatterPlot, ScatterPlotSeries, SERIES_COLORS},
+    stacked_area::{StackedArea, StackedAreaDay, StackedAreaSeries},
+};
 
 use super::{
+    audit::{AuditBuilder, AuditIssue},
     date_map::{BTreeDateMap, OrderedNaiveDateTimeSpan},
-    symptoms::symptom::Symptom,
+    factors::factor::Factor,
+    keywords::{rank_keywords, term_frequencies_by_day, KeywordScore},
+    medications::medication::Medication,
+    notes::note::Note,
+    parser::CsvRecord,
+    query::{EntryQuery, QueryEntry},
+    search::NoteQuery,
+    symptoms::{severity_scale::{SeverityScale, SeverityScales}, symptom::{Symptom, SymptomParseError, SYMPTOM_CATEGORY}, symptom_aliases::SymptomAliases, symptom_group::SymptomGroup, symptom_id::{SymptomId, SymptomRegistry}},
+    time_of_day::TimeOfDay,
 };
 
 pub struct DataManager {
-    symptoms: HashMap<String, BTreeDateMap<Symptom>>,
+    registry: SymptomRegistry,
+    /// Indexed by `SymptomId`, the logged symptoms come first and the groups' combined entries after them
+    symptoms: Vec<BTreeDateMap<Symptom>>,
+    /// How many of the ids are logged symptoms rather than groups
+    symptom_count: usize,
+    scales: SeverityScales,
+    notes: Vec<Note>,
+    medications: Vec<Medication>,
+    factors: Vec<Factor>,
+    audit_issues: Vec<AuditIssue>,
 }
 
-impl DataManager {
-    pub fn from(symptoms: Vec<Symptom>) -> DataManager {
-        let mut categorized_symptoms = HashMap::<String, BTreeDateMap<Symptom>>::new();
-        for symptom in symptoms {
-            debug!("Processing {:?}", symptom);
-            if !categorized_symptoms.contains_key(&symptom.name) {
-                categorized_symptoms.insert(symptom.name.to_string(), BTreeDateMap::<Symptom>::new());
-                debug!("Inserting {}", &symptom.name);
+/// Collects a `DataManager` one row at a time, so an export can be read without keeping all its rows around
+#[derive(Default)]
+pub struct DataManagerBuilder {
+    registry: SymptomRegistry,
+    symptoms: Vec<BTreeDateMap<Symptom>>,
+    notes: Vec<Note>,
+    medications: Vec<Medication>,
+    factors: Vec<Factor>,
+    /// Symptom ids by the detail they were parsed from, e.g. "Neck pain (Mild)", so each detail is only parsed once
+    details: HashMap<String, SymptomId>,
+    aliases: SymptomAliases,
+    /// Scales set by name, their labels are taken off details like Bearable's are
+    scales: SeverityScales,
+    audit: AuditBuilder,
+}
+
+impl DataManagerBuilder {
+    /// Rolls entries logged under an old name up into the current one
+    pub fn with_aliases(mut self, aliases: SymptomAliases) -> DataManagerBuilder {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Reads details labelled on these scales, e.g. "Mood (Great)", as the symptom they're set for
+    pub fn with_scales(mut self, scales: SeverityScales) -> DataManagerBuilder {
+        self.scales = scales;
+        self
+    }
+
+    /// The symptom a detail is logged under, once its severity label is taken off and its alias resolved
+    fn symptom_name(&self, detail: &str) -> String {
+        if let Some((name, label)) = Symptom::split_label(detail) {
+            let name = self.aliases.resolve(name);
+            if self.scales.get(name).and_then(|scale| scale.value(label)).is_some() {
+                return name.to_string();
             }
-            let date_map = categorized_symptoms.get_mut(&symptom.name).expect("Should have just added it");
-            date_map.insert(symptom.date_time_span().unwrap(), symptom);
         }
+        self.aliases.resolve(&Symptom::parse_name(detail)).to_string()
+    }
+
+    pub fn add_row(&mut self, row: &CsvRecord) -> Result<(), SymptomParseError> {
+        if row.category == SYMPTOM_CATEGORY {
+            let id = match self.details.get(row.detail) {
+                Some(id) => *id,
+                None => {
+                    let id = self.registry.intern(&self.symptom_name(row.detail));
+                    self.details.insert(row.detail.to_string(), id);
+                    id
+                }
+            };
+            let symptom = Symptom::from(row, id)?;
+            self.audit.add_row(row, self.registry.name(id));
+            self.add_symptom(symptom);
+        } else {
+            self.audit.add_row(row, row.detail);
+        }
+        self.notes.extend(Note::from(row));
+        self.medications.extend(Medication::from(row));
+        self.factors.extend(Factor::from(row));
+        Ok(())
+    }
+
+    pub fn intern(&mut self, symptom_name: &str) -> SymptomId {
+        self.registry.intern(symptom_name)
+    }
+
+    /// The symptom's id has to come from `intern` on this builder
+    pub fn add_symptom(&mut self, symptom: Symptom) {
+        debug!("Processing {:?}", symptom);
+        if self.symptoms.len() < self.registry.len() {
+            self.symptoms.resize_with(self.registry.len(), BTreeDateMap::new);
+        }
+        let map = &mut self.symptoms[symptom.id.index()];
+        let mut span = symptom.date_time_span().unwrap();
+        // Untimed and all day entries share the whole day, each gets its own slot instead of replacing the last one
+        while map.contains_key(&span) {
+            span = span.next_slot().unwrap();
+        }
+        map.insert(span, symptom);
+    }
+
+    pub fn build(mut self) -> DataManager {
+        self.symptoms.resize_with(self.registry.len(), BTreeDateMap::new);
+        DataManager {
+            symptom_count: self.registry.len(),
+            scales: self.scales,
+            registry: self.registry,
+            symptoms: self.symptoms,
+            notes: Vec::new(),
+            medications: Vec::new(),
+            factors: Vec::new(),
+            audit_issues: self.audit.build(),
+        }
+        .with_notes(self.notes)
+        .with_medications(self.medications)
+        .with_factors(self.factors)
+    }
+}
+
+/// Only the logged entries are serialized, the per-symptom index is rebuilt from them when deserializing.
+/// Groups are left out, they're set again with `set_groups`.
+impl Serialize for DataManager {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut registry = self.registry.clone();
+        registry.truncate(self.symptom_count);
+        let symptoms = self.symptoms[..self.symptom_count].iter().flat_map(|date_map| date_map.values()).collect::<Vec<&Symptom>>();
+        (&registry, symptoms, &self.notes, &self.medications, &self.factors, &self.audit_issues, &self.scales).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DataManager {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DataManager, D::Error> {
+        let (registry, symptoms, notes, medications, factors, audit_issues, scales) =
+            <(SymptomRegistry, Vec<Symptom>, Vec<Note>, Vec<Medication>, Vec<Factor>, Vec<AuditIssue>, SeverityScales)>::deserialize(deserializer)?;
+        let mut builder = DataManagerBuilder { registry, scales, ..DataManagerBuilder::default() };
+        for symptom in symptoms {
+            builder.add_symptom(symptom);
+        }
+        let mut data_manager = builder.build().with_notes(notes).with_medications(medications).with_factors(factors);
+        data_manager.audit_issues = audit_issues;
+        Ok(data_manager)
+    }
+}
+
+impl DataManager {
+    pub fn with_notes(mut self, mut notes: Vec<Note>) -> DataManager {
+        notes.sort_by_key(|note| note.date);
+        self.notes = notes;
+        self
+    }
+
+    pub fn get_daily_term_frequencies(&self) -> BTreeMap<NaiveDate, HashMap<String, usize>> {
+        term_frequencies_by_day(&self.notes)
+    }
+
+    /// Note terms that show up more on days where the symptom reached `high_severity` than on other days
+    pub fn get_symptom_keywords(&self, symptom: SymptomId, high_severity: u8) -> Option<Vec<KeywordScore>> {
+        let high_days = self.get_daily_max_severities(symptom, ..)?
+            .into_iter()
+            .filter(|(_, severity)| *severity >= high_severity)
+            .map(|(date, _)| date)
+            .collect::<BTreeSet<NaiveDate>>();
+        Some(rank_keywords(&self.get_daily_term_frequencies(), &high_days))
+    }
+
+    pub fn with_medications(mut self, mut medications: Vec<Medication>) -> DataManager {
+        medications.sort_by_key(|medication| medication.date);
+        self.medications = medications;
+        self
+    }
+
+    pub fn with_factors(mut self, mut factors: Vec<Factor>) -> DataManager {
+        factors.sort_by_key(|factor| factor.date);
+        self.factors = factors;
+        self
+    }
+
+    pub fn get_factors(&self) -> &[Factor] {
+        &self.factors
+    }
+
+    pub fn get_medications(&self) -> &[Medication] {
+        &self.medications
+    }
+
+    /// Suspicious rows and days found while reading the export, oldest first
+    pub fn get_audit_issues(&self) -> &[AuditIssue] {
+        &self.audit_issues
+    }
+
+    pub fn get_notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    /// Notes matching the query, oldest first
+    pub fn search_notes(&self, query: &NoteQuery) -> Vec<&Note> {
+        self.notes.iter().filter(|note| query.matches(&note.text())).collect()
+    }
+
+    /// A copy with only the symptom and group entries matching the query, everything else is kept as it is
+    pub fn filtered(&self, query: &EntryQuery) -> DataManager {
+        let mut day_factors = BTreeMap::<NaiveDate, BTreeSet<String>>::new();
+        for factor in &self.factors {
+            day_factors.entry(factor.date).or_default().insert(factor.name.to_lowercase());
+        }
+        let no_factors = BTreeSet::new();
 
+        let symptoms = self.symptoms.iter()
+            .map(|map| {
+                let mut filtered = BTreeDateMap::new();
+                for (span, symptom) in map.iter() {
+                    let entry = QueryEntry {
+                        symptom,
+                        name: self.registry.name(symptom.id),
+                        factors: day_factors.get(&symptom.date).unwrap_or(&no_factors),
+                    };
+                    if query.matches(&entry) {
+                        filtered.insert(span.clone(), symptom.clone());
+                    }
+                }
+                filtered
+            })
+            .collect();
         DataManager {
-            symptoms: categorized_symptoms,
+            registry: self.registry.clone(),
+            symptoms,
+            symptom_count: self.symptom_count,
+            scales: self.scales.clone(),
+            notes: self.notes.clone(),
+            medications: self.medications.clone(),
+            factors: self.factors.clone(),
+            audit_issues: self.audit_issues.clone(),
+        }
+    }
+
+    /// Replaces the groups, each one can then be queried like a symptom through its id.
+    /// Groups named like a logged symptom are skipped.
+    pub fn set_groups(&mut self, groups: &[SymptomGroup]) {
+        self.registry.truncate(self.symptom_count);
+        self.symptoms.truncate(self.symptom_count);
+        for group in groups {
+            if self.registry.id(&group.name).is_some() {
+                debug!("Skipping group {:?} named like a symptom", group.name);
+                continue;
+            }
+            let entries = self.combine_group(group);
+            let id = self.registry.intern(&group.name);
+            self.symptoms.push(BTreeDateMap::new());
+            for (span, mut symptom) in entries {
+                symptom.id = id;
+                self.symptoms[id.index()].insert(span, symptom);
+            }
+        }
+    }
+
+    /// One entry per time of day any member was logged in, with the members' severities combined
+    fn combine_group(&self, group: &SymptomGroup) -> BTreeMap<OrderedNaiveDateTimeSpan, Symptom> {
+        let mut slots = BTreeMap::<OrderedNaiveDateTimeSpan, Vec<&Symptom>>::new();
+        for id in self.get_symptom_ids_for(&group.members) {
+            for (span, symptom) in self.symptoms[id.index()].iter() {
+                slots.entry(span.clone()).or_default().push(symptom);
+            }
         }
+        slots.into_iter()
+            .map(|(span, symptoms)| {
+                let notes = symptoms.iter().map(|s| s.notes.as_str()).filter(|n| !n.is_empty()).collect::<Vec<&str>>().join("; ");
+                let symptom = Symptom {
+                    severity: group.combination.combine(symptoms.iter().map(|s| s.severity)),
+                    notes,
+                    ..symptoms[0].clone()
+                };
+                (span, symptom)
+            })
+            .collect()
+    }
+
+    /// Replaces the scales set for symptoms and groups by name
+    pub fn set_severity_scales(&mut self, scales: SeverityScales) {
+        self.scales = scales;
+    }
+
+    /// The scale set for the symptom, or the one its severities fit on
+    pub fn get_severity_scale(&self, symptom: SymptomId) -> SeverityScale {
+        match self.scales.get(self.registry.name(symptom)) {
+            Some(scale) => scale.clone(),
+            None => {
+                let max_value = self.symptoms.get(symptom.index()).and_then(|map| map.values().map(|s| s.severity).max());
+                SeverityScale::infer(max_value.unwrap_or_default())
+            }
+        }
+    }
+
+    /// Every logged symptom, alphabetically by name
+    pub fn get_symptom_ids(&self) -> Vec<SymptomId> {
+        self.registry.sorted_ids().into_iter().filter(|id| !self.is_group(*id)).collect()
+    }
+
+    /// Every group set with `set_groups`, alphabetically by name
+    pub fn get_group_ids(&self) -> Vec<SymptomId> {
+        self.registry.sorted_ids().into_iter().filter(|id| self.is_group(*id)).collect()
     }
 
-    pub fn get_symptom_names(&self) -> Vec<&String> {
-        Vec::from_iter(self.symptoms.keys().into_iter())
+    pub fn is_group(&self, symptom: SymptomId) -> bool {
+        symptom.index() >= self.symptom_count
     }
 
-    pub fn get_symptom_date_range(&self, symptom_name: &str) -> Option<RangeInclusive<NaiveDateTime>> {
-        let map = self.symptoms.get(symptom_name)?;
+    pub fn get_symptom_id(&self, symptom_name: &str) -> Option<SymptomId> {
+        self.registry.id(symptom_name)
+    }
+
+    /// Ids of the symptom and group names this export has, skipping the rest
+    pub fn get_symptom_ids_for(&self, symptom_names: &[String]) -> Vec<SymptomId> {
+        symptom_names.iter().filter_map(|name| self.registry.id(name)).collect()
+    }
+
+    pub fn get_symptom_name(&self, symptom: SymptomId) -> &str {
+        self.registry.name(symptom)
+    }
+
+    pub fn get_symptom_names(&self, symptoms: &[SymptomId]) -> Vec<String> {
+        symptoms.iter().map(|id| self.registry.name(*id).to_string()).collect()
+    }
+
+    pub fn get_symptom_date_range(&self, symptom: SymptomId) -> Option<RangeInclusive<NaiveDateTime>> {
+        let map = self.symptoms.get(symptom.index())?;
         let min = map.min()?.0.start;
         let max = map.max()?.0.start;
         return Some(min..=max)
     }
 
-    pub fn get_all_sorted_symptoms(&self, symptom_name: &str) -> Option<Vec<&Symptom>> {
-        let map = self.symptoms.get(symptom_name)?;
-        Some(Vec::from_iter(map.values().into_iter()))
+    pub fn get_symptoms_date_range(&self, symptoms: &[SymptomId]) -> Option<RangeInclusive<NaiveDateTime>> {
+        let ranges = symptoms.iter().filter_map(|id| self.get_symptom_date_range(*id)).collect::<Vec<_>>();
+        let min = ranges.iter().map(|r| *r.start()).min()?;
+        let max = ranges.iter().map(|r| *r.end()).max()?;
+        Some(min..=max)
+    }
+
+    pub fn get_all_sorted_symptoms(&self, symptom: SymptomId) -> Option<Vec<&Symptom>> {
+        let map = self.symptoms.get(symptom.index())?;
+        Some(map.values().collect())
+    }
+
+    pub fn get_basic_symptoms_scatterplot<R>(&self, symptoms: &[SymptomId], range: R) -> Option<ScatterPlot>
+        where R: RangeBounds<OrderedNaiveDateTimeSpan> + Clone
+    {
+        let mut series = Vec::new();
+        for (index, symptom) in symptoms.iter().enumerate() {
+            let map = self.symptoms.get(symptom.index())?;
+            let values = map.range(range.clone())
+                .map(|(k, v)| DateTimeValuePoint {
+                    x: k.start,
+                    y: v.severity,
+                    time_of_day: v.time_of_day,
+                    notes: v.notes.to_owned(),
+                })
+                .collect::<Vec<DateTimeValuePoint>>();
+            series.push(ScatterPlotSeries::new(self.registry.name(*symptom), index, self.get_severity_scale(*symptom), values));
+        }
+        Some(ScatterPlot { series })
+    }
+
+    /// Like `get_basic_symptoms_scatterplot`, but with one point per day
+    pub fn get_daily_symptoms_scatterplot<R>(&self, symptoms: &[SymptomId], range: R, reducer: Reducer) -> Option<ScatterPlot>
+        where R: RangeBounds<OrderedNaiveDateTimeSpan> + Clone
+    {
+        let mut series = Vec::new();
+        for (index, symptom) in symptoms.iter().enumerate() {
+            let map = self.symptoms.get(symptom.index())?;
+            let mut days = BTreeMap::<NaiveDate, Vec<&Symptom>>::new();
+            for entry in map.range(range.clone()).map(|(_, v)| v) {
+                days.entry(entry.date).or_default().push(entry);
+            }
+            let values = days.into_iter()
+                .map(|(date, entries)| {
+                    let severities = entries.iter().map(|s| s.severity);
+                    DateTimeValuePoint {
+                        x: date.and_hms(0, 0, 0),
+                        y: reducer.reduce(severities),
+                        time_of_day: TimeOfDay::AllDay,
+                        notes: entries.iter().map(|s| s.notes.as_str()).filter(|n| !n.is_empty()).collect::<Vec<&str>>().join("; "),
+                    }
+                })
+                .collect::<Vec<DateTimeValuePoint>>();
+            // Totals can go past the scale's top and don't mean its levels any more
+            let scale = self.get_severity_scale(*symptom);
+            let scale = match reducer {
+                Reducer::Sum => SeverityScale::numeric(0, values.iter().map(|p| p.y).fold(scale.max, u8::max)),
+                Reducer::Max => scale,
+            };
+            series.push(ScatterPlotSeries::new(self.registry.name(*symptom), index, scale, values));
+        }
+        Some(ScatterPlot { series })
+    }
+
+    /// Groups every recorded severity of the symptom by the day it was logged on
+    pub fn get_daily_severities<R>(&self, symptom: SymptomId, range: R) -> Option<BTreeMap<NaiveDate, Vec<u8>>>
+        where R: RangeBounds<OrderedNaiveDateTimeSpan>
+    {
+        let map = self.symptoms.get(symptom.index())?;
+        let mut days = BTreeMap::<NaiveDate, Vec<u8>>::new();
+        for entry in map.range(range).map(|(_, v)| v) {
+            days.entry(entry.date).or_default().push(entry.severity);
+        }
+        Some(days)
+    }
+
+    pub fn get_daily_max_severities<R>(&self, symptom: SymptomId, range: R) -> Option<BTreeMap<NaiveDate, u8>>
+        where R: RangeBounds<OrderedNaiveDateTimeSpan>
+    {
+        let days = self.get_daily_severities(symptom, range)?;
+        Some(days.into_iter()
+            .map(|(date, severities)| (date, severities.into_iter().max().unwrap_or_default()))
+            .collect())
+    }
+
+    pub fn get_daily_summed_severities<R>(&self, symptom: SymptomId, range: R) -> Option<BTreeMap<NaiveDate, u8>>
+        where R: RangeBounds<OrderedNaiveDateTimeSpan>
+    {
+        let days = self.get_daily_severities(symptom, range)?;
+        Some(days.into_iter()
+            .map(|(date, severities)| (date, severities.into_iter().fold(0u8, |sum, s| sum.saturating_add(s))))
+            .collect())
+    }
+
+    /// Daily severity sums of every symptom, stacked so the total shows the overall burden of each day
+    pub fn get_symptom_burden<R>(&self, range: R) -> StackedArea
+        where R: RangeBounds<OrderedNaiveDateTimeSpan> + Clone
+    {
+        let symptoms = self.get_symptom_ids();
+        let daily_sums = symptoms.iter()
+            .filter_map(|id| self.get_daily_summed_severities(*id, range.clone()))
+            .collect::<Vec<BTreeMap<NaiveDate, u8>>>();
+        let dates = daily_sums.iter().flat_map(|sums| sums.keys().copied()).collect::<BTreeSet<NaiveDate>>();
+
+        StackedArea {
+            series: symptoms.iter()
+                .enumerate()
+                .map(|(index, id)| StackedAreaSeries {
+                    name: self.registry.name(*id).to_string(),
+                    color: SERIES_COLORS[index % SERIES_COLORS.len()].to_string(),
+                })
+                .collect(),
+            days: dates.into_iter()
+                .map(|date| StackedAreaDay {
+                    date,
+                    values: daily_sums.iter().map(|sums| sums.get(&date).copied().unwrap_or_default()).collect(),
+                })
+                .collect(),
+        }
     }
 
-    pub fn get_basic_symptoms_scatterplot<R>(&self, symptom_name: &str, range: R) -> Option<ScatterPlot>
+    pub fn get_calendar_heatmap<R>(&self, symptom: SymptomId, range: R, reducer: Reducer) -> Option<CalendarHeatmap>
         where R: RangeBounds<OrderedNaiveDateTimeSpan>
     {
-        let map = self.symptoms.get(symptom_name)?;
-        let values = map.range(range)
+        let days = self.get_daily_severities(symptom, range)?
             .into_iter()
-            .map(|(k, v)| DateTimeValuePoint {
-                x: k.start,
-                y: v.severity
-            })
-            .collect::<Vec<DateTimeValuePoint>>();
-        return Some(ScatterPlot { points: values });
+            .map(|(date, severities)| DateValuePoint { date, value: reducer.reduce(severities.into_iter()) })
+            .collect::<Vec<DateValuePoint>>();
+        Some(CalendarHeatmap {
+            name: self.registry.name(symptom).to_string(),
+            max_value: days.iter().map(|d| d.value).max().unwrap_or_default(),
+            days,
+        })
+    }
+
+    /// Every symptom logged on the given day, in time of day order
+    pub fn get_day_log(&self, date: NaiveDate) -> Vec<&Symptom> {
+        let mut entries = self.symptoms[..self.symptom_count].iter()
+            .flat_map(|map| map.iter().filter(|(_, v)| v.date == date))
+            .collect::<Vec<(&OrderedNaiveDateTimeSpan, &Symptom)>>();
+        entries.sort_by(|(a_span, a), (b_span, b)| a_span.start.cmp(&b_span.start).then_with(|| self.registry.compare(a.id, b.id)));
+        entries.into_iter().map(|(_, v)| v).collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use assertables::*;
     use chrono::NaiveDate;
-    use wasm_bindgen::__rt::assert_not_null;
 
-    use crate::model::time_of_day::TimeOfDay;
+    use crate::model::{symptoms::symptom_group::GroupCombination, time_of_day::TimeOfDay};
 
     use super::*;
 
+    /// Builds a `DataManager` from (name, date, time of day, severity, notes) entries
+    fn data_manager(entries: &[(&str, NaiveDate, TimeOfDay, u8, &str)]) -> DataManager {
+        let mut builder = DataManagerBuilder::default();
+        for (name, date, time_of_day, severity, notes) in entries {
+            let id = builder.intern(name);
+            builder.add_symptom(Symptom { id, date: *date, time_of_day: *time_of_day, severity: *severity, notes: notes.to_string() });
+        }
+        builder.build()
+    }
+
     #[test]
     fn GetSymptomDateRange_DoesThingsIdk() {
         let symptom_name = "Back (mid) pain";
-        let symptoms = vec![
-            Symptom {
-                date: NaiveDate::from_ymd(2022, 1, 5),
-                name: symptom_name.to_string(),
-                severity: 1,
-                time_of_day: TimeOfDay::Pre,
-            },
-            Symptom {
-                date: NaiveDate::from_ymd(2022, 1, 6),
-                name: symptom_name.to_string(),
-                severity: 3,
-                time_of_day: TimeOfDay::Pre,
-            },
-        ];
-        let data_man = DataManager::from(symptoms.clone());
-
-        let range = data_man.get_symptom_date_range(symptom_name);
+        let dates = [NaiveDate::from_ymd(2022, 1, 5), NaiveDate::from_ymd(2022, 1, 6)];
+        let data_man = data_manager(&[
+            (symptom_name, dates[0], TimeOfDay::Pre, 1, ""),
+            (symptom_name, dates[1], TimeOfDay::Pre, 3, ""),
+        ]);
+
+        let range = data_man.get_symptom_date_range(data_man.get_symptom_id(symptom_name).unwrap());
 
         assert!(range.is_some());
-        assert_eq!(range.as_ref().expect("").start().date(), symptoms[0].date);
-        assert_eq!(range.as_ref().expect("").end().date(), symptoms[1].date);
+        assert_eq!(range.as_ref().expect("").start().date(), dates[0]);
+        assert_eq!(range.as_ref().expect("").end().date(), dates[1]);
+    }
+
+    #[test]
+    fn AddSymptom_ForOverlappingTimesOfDay_KeepsEveryEntry() {
+        let date = NaiveDate::from_ymd(2022, 1, 5);
+        let data_man = data_manager(&[
+            ("Neck pain", date, TimeOfDay::AllDay, 2, ""),
+            ("Neck pain", date, TimeOfDay::AM, 1, ""),
+            ("Neck pain", date, TimeOfDay::None, 3, ""),
+            ("Neck pain", date, TimeOfDay::None, 4, ""),
+        ]);
+
+        let neck_pain = data_man.get_symptom_id("Neck pain").unwrap();
+        let severities = data_man.get_all_sorted_symptoms(neck_pain).unwrap().iter().map(|s| s.severity).collect::<Vec<u8>>();
+
+        assert_eq!(severities.len(), 4, "{:?}", severities);
+    }
+
+    #[test]
+    fn AddSymptom_ForUntimedThenAllDayEntry_KeepsBoth() {
+        let date = NaiveDate::from_ymd(2022, 1, 5);
+        let data_man = data_manager(&[
+            ("Neck pain", date, TimeOfDay::None, 3, ""),
+            ("Neck pain", date, TimeOfDay::AllDay, 2, ""),
+        ]);
+
+        let neck_pain = data_man.get_symptom_id("Neck pain").unwrap();
+        let severities = data_man.get_all_sorted_symptoms(neck_pain).unwrap().iter().map(|s| s.severity).collect::<Vec<u8>>();
+
+        assert_eq!(severities, vec![3, 2]);
+    }
+
+    #[test]
+    fn GetBasicSymptomsScatterplot_ForMultipleSymptoms_ReturnsOneSeriesPerSymptom() {
+        let data_man = data_manager(&[
+            ("Neck pain", NaiveDate::from_ymd(2022, 1, 5), TimeOfDay::AM, 2, ""),
+            ("Headache", NaiveDate::from_ymd(2022, 1, 5), TimeOfDay::PM, 1, ""),
+            ("Headache", NaiveDate::from_ymd(2022, 1, 6), TimeOfDay::AM, 3, ""),
+        ]);
+        let ids = data_man.get_symptom_ids_for(&["Neck pain".to_string(), "Headache".to_string()]);
+
+        let scatter_plot = data_man.get_basic_symptoms_scatterplot(&ids, ..).unwrap();
+
+        assert_eq!(scatter_plot.series.len(), 2);
+        assert_eq!(scatter_plot.series[0].name, "Neck pain");
+        assert_eq!(scatter_plot.series[0].points.len(), 1);
+        assert_eq!(scatter_plot.series[1].name, "Headache");
+        assert_eq!(scatter_plot.series[1].points.iter().map(|p| p.y).collect::<Vec<u8>>(), vec![1, 3]);
+        assert_ne!(scatter_plot.series[0].color, scatter_plot.series[1].color);
+    }
+
+    #[test]
+    fn GetDailyMaxSeverities_ForSeveralEntriesPerDay_KeepsTheWorst() {
+        let symptom_name = "Neck pain";
+        let data_man = data_manager(&[
+            (symptom_name, NaiveDate::from_ymd(2022, 1, 5), TimeOfDay::AM, 1, ""),
+            (symptom_name, NaiveDate::from_ymd(2022, 1, 5), TimeOfDay::PM, 3, ""),
+            (symptom_name, NaiveDate::from_ymd(2022, 1, 6), TimeOfDay::MID, 2, ""),
+            ("Headache", NaiveDate::from_ymd(2022, 1, 6), TimeOfDay::AM, 4, ""),
+        ]);
+
+        let daily = data_man.get_daily_max_severities(data_man.get_symptom_id(symptom_name).unwrap(), ..).unwrap();
+        let day_log = data_man.get_day_log(NaiveDate::from_ymd(2022, 1, 6));
+
+        assert_eq!(daily.get(&NaiveDate::from_ymd(2022, 1, 5)), Some(&3));
+        assert_eq!(daily.get(&NaiveDate::from_ymd(2022, 1, 6)), Some(&2));
+        assert_eq!(day_log.iter().map(|s| data_man.get_symptom_name(s.id)).collect::<Vec<&str>>(), vec!["Headache", "Neck pain"]);
+    }
+
+    #[test]
+    fn GetSymptomBurden_ForMissingDays_FillsZeroesPerSymptom() {
+        let data_man = data_manager(&[
+            ("Neck pain", NaiveDate::from_ymd(2022, 1, 5), TimeOfDay::AM, 1, ""),
+            ("Neck pain", NaiveDate::from_ymd(2022, 1, 5), TimeOfDay::PM, 2, ""),
+            ("Headache", NaiveDate::from_ymd(2022, 1, 6), TimeOfDay::AM, 4, ""),
+        ]);
+
+        let burden = data_man.get_symptom_burden(..);
+
+        assert_eq!(burden.series.iter().map(|s| s.name.as_str()).collect::<Vec<&str>>(), vec!["Headache", "Neck pain"]);
+        assert_eq!(burden.days.len(), 2);
+        assert_eq!(burden.days[0].values, vec![0, 3]);
+        assert_eq!(burden.days[1].values, vec![4, 0]);
+    }
+
+    #[test]
+    fn GetAggregatedSymptomsScatterplot_ForDailySum_ReturnsOnePointPerDay() {
+        let date = NaiveDate::from_ymd(2022, 1, 5);
+        let data_man = data_manager(&[
+            ("Neck pain", date, TimeOfDay::AM, 1, "stiff"),
+            ("Neck pain", date, TimeOfDay::PM, 3, ""),
+            ("Neck pain", date, TimeOfDay::MID, 2, "worse"),
+        ]);
+        let ids = data_man.get_symptom_ids();
+
+        let scatter_plot = data_man.get_daily_symptoms_scatterplot(&ids, .., Reducer::Sum).unwrap();
+
+        let points = &scatter_plot.series[0].points;
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].y, 6);
+        assert_eq!(points[0].notes, "stiff; worse");
+        assert_eq!(scatter_plot.series[0].scale, SeverityScale::numeric(0, 6));
+    }
+
+    #[test]
+    fn GetSeverityScale_ForSetAndUnsetScales_FallsBackToInferring() {
+        let date = NaiveDate::from_ymd(2022, 1, 5);
+        let mut data_man = data_manager(&[
+            ("Neck pain", date, TimeOfDay::AM, 2, ""),
+            ("Pain score", date, TimeOfDay::AM, 7, ""),
+            ("Mood", date, TimeOfDay::AM, 4, ""),
+        ]);
+        let mood = SeverityScale::with_labels(1, 5, "Awful,Bad,Meh,Good,Great").unwrap();
+        let mut scales = SeverityScales::default();
+        scales.set("Mood", mood.clone());
+
+        data_man.set_severity_scales(scales);
+
+        let scale = |name| data_man.get_severity_scale(data_man.get_symptom_id(name).unwrap());
+        assert_eq!(scale("Neck pain"), SeverityScale::default());
+        assert_eq!(scale("Pain score"), SeverityScale::numeric(0, 10));
+        assert_eq!(scale("Mood"), mood);
+    }
+
+    #[test]
+    fn Deserialize_ForSerializedDataManagerWithScales_KeepsTheScales() {
+        let mood = SeverityScale::with_labels(1, 5, "Awful,Bad,Meh,Good,Great").unwrap();
+        let mut scales = SeverityScales::default();
+        scales.set("Mood", mood.clone());
+        let mut builder = DataManagerBuilder::default().with_scales(scales);
+        let id = builder.intern("Mood");
+        builder.add_symptom(Symptom { id, date: NaiveDate::from_ymd(2022, 1, 5), time_of_day: TimeOfDay::AM, severity: 4, notes: String::new() });
+
+        let restored: DataManager = serde_json::from_str(&serde_json::to_string(&builder.build()).unwrap()).unwrap();
+
+        assert_eq!(restored.get_severity_scale(restored.get_symptom_id("Mood").unwrap()), mood);
+    }
+
+    #[test]
+    fn Filtered_ForQueryWithFactor_KeepsMatchingEntriesOnly() {
+        let saturday = NaiveDate::from_ymd(2022, 1, 8);
+        let sunday = NaiveDate::from_ymd(2022, 1, 9);
+        let data_man = data_manager(&[
+            ("Headache", saturday, TimeOfDay::AM, 3, ""),
+            ("Headache", saturday, TimeOfDay::PM, 1, ""),
+            ("Headache", sunday, TimeOfDay::AM, 4, ""),
+            ("Neck pain", saturday, TimeOfDay::AM, 3, ""),
+        ])
+        .with_factors(vec![Factor { date: saturday, time_of_day: TimeOfDay::AM, name: "Alcohol".to_string() }]);
+        let query = EntryQuery::parse(r#"severity >= 3 and factor:alcohol and symptom = headache"#).unwrap();
+
+        let filtered = data_man.filtered(&query);
+
+        let headache = filtered.get_all_sorted_symptoms(filtered.get_symptom_id("Headache").unwrap()).unwrap();
+        assert_eq!(headache.iter().map(|s| (s.date, s.severity)).collect::<Vec<_>>(), vec![(saturday, 3)]);
+        assert!(filtered.get_all_sorted_symptoms(filtered.get_symptom_id("Neck pain").unwrap()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn GetSymptomIds_ForSymptomsLoggedInAnyOrder_AreAlphabetical() {
+        let date = NaiveDate::from_ymd(2022, 1, 5);
+        let data_man = data_manager(&[
+            ("Neck pain", date, TimeOfDay::AM, 1, ""),
+            ("back pain", date, TimeOfDay::AM, 1, ""),
+            ("Headache", date, TimeOfDay::AM, 1, ""),
+        ]);
+
+        let names = data_man.get_symptom_names(&data_man.get_symptom_ids());
+
+        assert_eq!(names, vec!["back pain", "Headache", "Neck pain"]);
+    }
+
+    #[test]
+    fn SetGroups_ForMaxAndSumGroups_CombinesMembersPerTimeOfDay() {
+        let date = NaiveDate::from_ymd(2022, 1, 5);
+        let mut data_man = data_manager(&[
+            ("Neck pain", date, TimeOfDay::AM, 1, "stiff"),
+            ("Back (lower) pain", date, TimeOfDay::AM, 3, ""),
+            ("Back (mid) pain", date, TimeOfDay::PM, 2, "sore"),
+            ("Headache", date, TimeOfDay::AM, 4, ""),
+        ]);
+        let members = vec!["Neck pain".to_string(), "Back (lower) pain".to_string(), "Back (mid) pain".to_string()];
+
+        data_man.set_groups(&[
+            SymptomGroup { name: "Musculoskeletal".to_string(), members: members.clone(), combination: GroupCombination::Max },
+            SymptomGroup { name: "Musculoskeletal (sum)".to_string(), members, combination: GroupCombination::Sum },
+            SymptomGroup { name: "Headache".to_string(), members: Vec::new(), combination: GroupCombination::Max },
+        ]);
+
+        let max = data_man.get_symptom_id("Musculoskeletal").unwrap();
+        let sum = data_man.get_symptom_id("Musculoskeletal (sum)").unwrap();
+        let entries = data_man.get_all_sorted_symptoms(max).unwrap();
+        assert_eq!(entries.iter().map(|s| s.severity).collect::<Vec<u8>>(), vec![3, 2]);
+        assert_eq!(entries[0].notes, "stiff");
+        let sums = data_man.get_all_sorted_symptoms(sum).unwrap().iter().map(|s| s.severity).collect::<Vec<u8>>();
+        assert_eq!(sums, vec![4, 2]);
+        assert_eq!(data_man.get_group_ids(), vec![max, sum]);
+        assert_eq!(data_man.get_symptom_ids().len(), 4);
+        assert_eq!(data_man.get_day_log(date).len(), 4);
     }
 }
@@ -0,0 +1,175 @@
+use std::{error::Error, fmt::{self, Display}};
+
+use chrono::NaiveDate;
+
+/// The ways Bearable has written dates into exports, depending on the app version and the phone's locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateFormat {
+    /// "8th Dec 2021", "8 déc. 2021", "8. Dez. 2021"
+    MonthName,
+    /// "2021-12-08"
+    Iso,
+    /// "08/12/2021", "8.12.2021", "08-12-2021"
+    DayFirst,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateParseError {
+    /// Doesn't look like any of the `DateFormat`s
+    UnrecognisedFormat(String),
+    /// Looks like a `DateFormat::MonthName` date, but the month isn't one we know in any language
+    UnknownMonth(String),
+    /// The right shape, but not a day on the calendar, e.g. "31st Feb 2022"
+    InvalidDate(String),
+}
+
+impl Display for DateParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateParseError::UnrecognisedFormat(date) => write!(f, "\"{}\" isn't in a date format we know", date),
+            DateParseError::UnknownMonth(date) => write!(f, "\"{}\" has a month we don't know", date),
+            DateParseError::InvalidDate(date) => write!(f, "\"{}\" isn't a valid date", date),
+        }
+    }
+}
+
+impl Error for DateParseError {}
+
+/// Month names and abbreviations in the languages Bearable is used in, lowercase and without a trailing "."
+const MONTH_NAMES: [&[&str]; 12] = [
+    &["jan", "january", "januar", "jän", "jänner", "janv", "janvier", "ene", "enero", "gen", "gennaio", "janeiro", "januari"],
+    &["feb", "february", "februar", "févr", "fevr", "février", "fév", "fev", "febrero", "febbraio", "fevereiro", "februari"],
+    &["mar", "march", "mär", "märz", "mars", "marzo", "mrt", "maart", "março"],
+    &["apr", "april", "avr", "avril", "abr", "abril", "aprile"],
+    &["may", "mai", "mayo", "mag", "maggio", "mei", "maio"],
+    &["jun", "june", "juni", "juin", "junio", "giu", "giugno", "junho"],
+    &["jul", "july", "juli", "juil", "juillet", "julio", "lug", "luglio", "julho"],
+    &["aug", "august", "août", "aout", "ago", "agosto", "augustus"],
+    &["sep", "sept", "september", "septembre", "septiembre", "set", "settembre", "setembro"],
+    &["oct", "october", "okt", "oktober", "octobre", "octubre", "ott", "ottobre", "out", "outubro"],
+    &["nov", "november", "novembre", "noviembre", "novembro"],
+    &["dec", "december", "dez", "dezember", "déc", "décembre", "dic", "diciembre", "dicembre", "dezembro"],
+];
+
+impl DateFormat {
+    pub const ALL: [DateFormat; 3] = [DateFormat::MonthName, DateFormat::Iso, DateFormat::DayFirst];
+
+    /// The first format every sample parses with, samples are usually the date column of an export's first rows.
+    /// Falls back to the first sample's format, so the samples that don't fit fail when they're parsed.
+    pub fn detect(samples: &[&str]) -> Result<DateFormat, DateParseError> {
+        let first = match samples.first() {
+            Some(first) => first,
+            None => return Ok(DateFormat::MonthName),
+        };
+        let parses = |format: &&DateFormat, sample: &str| format.parse(sample).is_ok();
+        let detected = DateFormat::ALL.iter()
+            .find(|format| samples.iter().all(|sample| parses(format, sample)))
+            .or_else(|| DateFormat::ALL.iter().find(|format| parses(format, first)));
+        match detected {
+            Some(format) => Ok(*format),
+            None => DateFormat::parse_any(first).map(|_| DateFormat::guess(first)),
+        }
+    }
+
+    /// Parses a date of unknown format, going by its shape
+    pub fn parse_any(date: &str) -> Result<NaiveDate, DateParseError> {
+        DateFormat::guess(date).parse(date)
+    }
+
+    /// The format a date looks like it's in, without checking it parses
+    fn guess(date: &str) -> DateFormat {
+        let date = date.trim();
+        if date.chars().any(char::is_alphabetic) {
+            DateFormat::MonthName
+        } else if date.len() >= 5 && date.as_bytes()[4] == b'-' {
+            DateFormat::Iso
+        } else {
+            DateFormat::DayFirst
+        }
+    }
+
+    pub fn parse(&self, date: &str) -> Result<NaiveDate, DateParseError> {
+        let trimmed = date.trim();
+        let (year, month, day) = match self {
+            DateFormat::MonthName => Self::month_name_parts(trimmed)?,
+            DateFormat::Iso => match Self::numeric_parts(trimmed, &['-']) {
+                Some([year, month, day]) if year > 999 => (year, month, day),
+                _ => return Err(DateParseError::UnrecognisedFormat(date.to_string())),
+            },
+            DateFormat::DayFirst => match Self::numeric_parts(trimmed, &['/', '.', '-']) {
+                Some([day, month, year]) if year > 999 => (year, month, day),
+                _ => return Err(DateParseError::UnrecognisedFormat(date.to_string())),
+            },
+        };
+        NaiveDate::from_ymd_opt(year as i32, month, day).ok_or_else(|| DateParseError::InvalidDate(date.to_string()))
+    }
+
+    /// Splits "8th Dec 2021" style dates into year, month and day, skipping words like the Spanish "de"
+    fn month_name_parts(date: &str) -> Result<(u32, u32, u32), DateParseError> {
+        let unrecognised = || DateParseError::UnrecognisedFormat(date.to_string());
+        let tokens = date.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty() && *token != "de")
+            .collect::<Vec<&str>>();
+        let (day, month, year) = match tokens.as_slice() {
+            [day, month, year] => (day, month, year),
+            _ => return Err(unrecognised()),
+        };
+
+        // "8th", "8.", "8º" all start with the day's digits
+        let day_digits = day.chars().take_while(char::is_ascii_digit).collect::<String>();
+        let day = day_digits.parse::<u32>().map_err(|_| unrecognised())?;
+        if year.len() != 4 {
+            return Err(unrecognised());
+        }
+        let year = year.parse::<u32>().map_err(|_| unrecognised())?;
+        let month_name = month.trim_end_matches('.').to_lowercase();
+        let month = MONTH_NAMES.iter()
+            .position(|names| names.contains(&month_name.as_str()))
+            .ok_or_else(|| DateParseError::UnknownMonth(date.to_string()))?;
+        Ok((year, month as u32 + 1, day))
+    }
+
+    fn numeric_parts(date: &str, separators: &[char]) -> Option<[u32; 3]> {
+        let parts = date.split(separators).map(|part| part.parse::<u32>().ok()).collect::<Option<Vec<u32>>>()?;
+        match parts.as_slice() {
+            [a, b, c] => Some([*a, *b, *c]),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn Parse_ForEachFormatVariant_ReturnsTheSameDate() {
+        let expected = NaiveDate::from_ymd(2021, 12, 8);
+
+        assert_eq!(DateFormat::MonthName.parse("8th Dec 2021"), Ok(expected));
+        assert_eq!(DateFormat::MonthName.parse("8 déc. 2021"), Ok(expected));
+        assert_eq!(DateFormat::MonthName.parse("8. Dez. 2021"), Ok(expected));
+        assert_eq!(DateFormat::MonthName.parse("8 de dic. de 2021"), Ok(expected));
+        assert_eq!(DateFormat::Iso.parse("2021-12-08"), Ok(expected));
+        assert_eq!(DateFormat::DayFirst.parse("08/12/2021"), Ok(expected));
+        assert_eq!(DateFormat::DayFirst.parse("8.12.2021"), Ok(expected));
+    }
+
+    #[test]
+    fn Parse_ForBadDates_ReturnsTypedErrors() {
+        assert_eq!(DateFormat::MonthName.parse("8th Foo 2021"), Err(DateParseError::UnknownMonth("8th Foo 2021".to_string())));
+        assert_eq!(DateFormat::MonthName.parse("31st Feb 2022"), Err(DateParseError::InvalidDate("31st Feb 2022".to_string())));
+        assert_eq!(DateFormat::Iso.parse("08/12/2021"), Err(DateParseError::UnrecognisedFormat("08/12/2021".to_string())));
+        assert_eq!(DateFormat::parse_any("yesterday"), Err(DateParseError::UnrecognisedFormat("yesterday".to_string())));
+    }
+
+    #[test]
+    fn Detect_ForSampleDates_PicksTheFormatThatParsesThemAll() {
+        assert_eq!(DateFormat::detect(&["8th Dec 2021", "9th Dec 2021"]), Ok(DateFormat::MonthName));
+        assert_eq!(DateFormat::detect(&["2021-12-08", "2021-12-09"]), Ok(DateFormat::Iso));
+        assert_eq!(DateFormat::detect(&["08-12-2021", "13-12-2021"]), Ok(DateFormat::DayFirst));
+        assert_eq!(DateFormat::detect(&[]), Ok(DateFormat::MonthName));
+        assert_eq!(DateFormat::detect(&["2021-12-08", "8th Dec 2021"]), Ok(DateFormat::Iso));
+        assert_eq!(DateFormat::detect(&["8th Foo 2021"]), Err(DateParseError::UnknownMonth("8th Foo 2021".to_string())));
+    }
+}
@@ -2,12 +2,26 @@ use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::ops::{Deref, DerefMut};
 
-use chrono::NaiveDate;
+use chrono::{Duration, NaiveDate};
 use timespan::NaiveDateTimeSpan;
 
+/// Ordered by start, and spans starting together longest first, so a whole day sorts right before
+/// the entries in it and overlapping spans, e.g. an all day entry and a morning one, stay apart
 #[derive(PartialEq, Debug, Clone)]
 pub struct OrderedNaiveDateTimeSpan(pub NaiveDateTimeSpan);
 
+impl OrderedNaiveDateTimeSpan {
+    /// The whole day, sorting before every entry logged on the day
+    pub fn day(date: NaiveDate) -> OrderedNaiveDateTimeSpan {
+        OrderedNaiveDateTimeSpan(NaiveDateTimeSpan::new(date.and_hms(0, 0, 0), date.and_hms(23, 59, 59)).expect("a day ends after it starts"))
+    }
+
+    /// The same span starting a second later, a distinct key for another entry logged without a time
+    pub fn next_slot(&self) -> Result<OrderedNaiveDateTimeSpan, timespan::Error> {
+        Ok(OrderedNaiveDateTimeSpan(NaiveDateTimeSpan::new(self.0.start + Duration::seconds(1), self.0.end)?))
+    }
+}
+
 impl Deref for OrderedNaiveDateTimeSpan {
     type Target = NaiveDateTimeSpan;
 
@@ -18,19 +32,13 @@ impl Deref for OrderedNaiveDateTimeSpan {
 
 impl Ord for OrderedNaiveDateTimeSpan {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(&other).unwrap()
+        self.0.start.cmp(&other.0.start).then_with(|| other.0.end.cmp(&self.0.end))
     }
 }
 
 impl PartialOrd for OrderedNaiveDateTimeSpan {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if &self.0.end < &other.0.start {
-            Some(Ordering::Less)
-        } else if &self.0.start > &other.0.end {
-            Some(Ordering::Greater)
-        } else {
-            Some(Ordering::Equal)
-        }
+        Some(self.cmp(other))
     }
 }
 
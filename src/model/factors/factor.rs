@@ -0,0 +1,30 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{parser::CsvRecord, time_of_day::TimeOfDay};
+
+pub const FACTOR_CATEGORY: &str = "Factors";
+
+/// Something that was part of a day, e.g. "Alcohol" or "Poor sleep"
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Factor {
+    pub date: NaiveDate,
+    pub time_of_day: TimeOfDay,
+    pub name: String,
+}
+
+impl Factor {
+    /// A factors row lists every factor of its check-in in the detail, separated by "|"
+    pub fn from(row: &CsvRecord) -> Vec<Factor> {
+        if row.category != FACTOR_CATEGORY {
+            return Vec::new();
+        }
+
+        let time_of_day = serde_plain::from_str::<TimeOfDay>(row.time_of_day).unwrap_or(TimeOfDay::None);
+        row.detail.split('|')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| Factor { date: row.date, time_of_day, name: name.to_string() })
+            .collect()
+    }
+}
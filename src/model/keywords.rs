@@ -0,0 +1,121 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::NaiveDate;
+
+use super::{notes::note::Note, search::tokenize};
+
+/// Days at or above this daily max severity count as bad days, i.e. Severe or worse
+pub const HIGH_SEVERITY_THRESHOLD: u8 = 3;
+
+/// Terms have to show up on at least this many days to be ranked at all
+const MIN_TERM_DAYS: usize = 2;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "after", "again", "all", "an", "and", "any", "are", "as", "at", "be", "been", "before", "but", "by", "can", "did", "do",
+    "for", "from", "got", "had", "has", "have", "i", "i'm", "in", "into", "is", "it", "just", "me", "my", "of", "on", "or", "out", "so",
+    "that", "the", "then", "there", "this", "to", "up", "very", "was", "we", "went", "were", "with",
+];
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct KeywordScore {
+    pub term: String,
+    pub high_days: usize,
+    pub other_days: usize,
+    /// How much more often the term appears on bad days than on other days, smoothed so rare terms don't dominate
+    pub lift: f64,
+}
+
+/// How often each meaningful term shows up in the notes of each day
+pub fn term_frequencies_by_day<'a, I>(notes: I) -> BTreeMap<NaiveDate, HashMap<String, usize>>
+    where I: IntoIterator<Item = &'a Note>
+{
+    let mut days = BTreeMap::<NaiveDate, HashMap<String, usize>>::new();
+    for note in notes {
+        let terms = days.entry(note.date).or_default();
+        for term in tokenize(&note.text()).into_iter().filter(|t| is_meaningful(t)) {
+            *terms.entry(term).or_default() += 1;
+        }
+    }
+    days
+}
+
+/// Ranks terms by how over-represented they are on the given bad days compared to all other days with notes
+pub fn rank_keywords(term_frequencies: &BTreeMap<NaiveDate, HashMap<String, usize>>, high_days: &BTreeSet<NaiveDate>) -> Vec<KeywordScore> {
+    let total_high_days = term_frequencies.keys().filter(|date| high_days.contains(date)).count();
+    let total_other_days = term_frequencies.len() - total_high_days;
+
+    let mut day_counts = HashMap::<&str, (usize, usize)>::new();
+    for (date, terms) in term_frequencies {
+        let is_high = high_days.contains(date);
+        for term in terms.keys() {
+            let counts = day_counts.entry(term.as_str()).or_default();
+            if is_high {
+                counts.0 += 1;
+            } else {
+                counts.1 += 1;
+            }
+        }
+    }
+
+    let mut scores = day_counts.into_iter()
+        .filter(|(_, (high, other))| high + other >= MIN_TERM_DAYS)
+        .map(|(term, (high, other))| KeywordScore {
+            term: term.to_string(),
+            high_days: high,
+            other_days: other,
+            lift: ((high as f64 + 1.0) / (total_high_days as f64 + 2.0)) / ((other as f64 + 1.0) / (total_other_days as f64 + 2.0)),
+        })
+        .collect::<Vec<KeywordScore>>();
+    scores.sort_by(|a, b| b.lift.partial_cmp(&a.lift).unwrap().then_with(|| a.term.cmp(&b.term)));
+    scores
+}
+
+fn is_meaningful(term: &str) -> bool {
+    term.len() > 1 && !STOP_WORDS.contains(&term) && !term.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::time_of_day::TimeOfDay;
+
+    use super::*;
+
+    fn note(day: u32, text: &str) -> Note {
+        Note {
+            date: NaiveDate::from_ymd(2022, 1, day),
+            time_of_day: TimeOfDay::None,
+            category: "Journal".to_string(),
+            detail: text.to_string(),
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn TermFrequenciesByDay_ForStopWords_SkipsThem() {
+        let notes = vec![note(1, "Pizza and a long drive"), note(1, "more pizza")];
+
+        let frequencies = term_frequencies_by_day(&notes);
+
+        let day = &frequencies[&NaiveDate::from_ymd(2022, 1, 1)];
+        assert_eq!(day.get("pizza"), Some(&2));
+        assert_eq!(day.get("and"), None);
+        assert_eq!(day.get("a"), None);
+    }
+
+    #[test]
+    fn RankKeywords_ForTermOnlyOnBadDays_RanksItFirst() {
+        let notes = vec![
+            note(1, "pizza walk"),
+            note(2, "pizza walk"),
+            note(3, "walk"),
+            note(4, "walk"),
+        ];
+        let high_days = [1, 2].iter().map(|d| NaiveDate::from_ymd(2022, 1, *d)).collect::<BTreeSet<NaiveDate>>();
+
+        let scores = rank_keywords(&term_frequencies_by_day(&notes), &high_days);
+
+        assert_eq!(scores[0].term, "pizza");
+        assert_eq!((scores[0].high_days, scores[0].other_days), (2, 0));
+        assert!(scores[0].lift > scores[1].lift);
+    }
+}
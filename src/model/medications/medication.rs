@@ -0,0 +1,29 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{parser::CsvRecord, time_of_day::TimeOfDay};
+
+pub const MEDICATION_CATEGORY: &str = "Meds/Supplements";
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Medication {
+    pub date: NaiveDate,
+    pub time_of_day: TimeOfDay,
+    pub name: String,
+    pub amount: String,
+}
+
+impl Medication {
+    pub fn from(row: &CsvRecord) -> Option<Medication> {
+        if row.category != MEDICATION_CATEGORY {
+            return None;
+        }
+
+        Some(Medication {
+            date: row.date,
+            time_of_day: serde_plain::from_str::<TimeOfDay>(row.time_of_day).unwrap_or(TimeOfDay::None),
+            name: row.detail.to_owned(),
+            amount: row.amount.to_owned(),
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{parser::CsvRecord, time_of_day::TimeOfDay};
+
+pub const JOURNAL_CATEGORY: &str = "Journal";
+
+/// Free text attached to any logged row, including the journal entries themselves
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct Note {
+    pub date: NaiveDate,
+    pub time_of_day: TimeOfDay,
+    pub category: String,
+    pub detail: String,
+    pub notes: String,
+}
+
+impl Note {
+    pub fn from(row: &CsvRecord) -> Option<Note> {
+        if row.notes.is_empty() && row.category != JOURNAL_CATEGORY {
+            return None;
+        }
+
+        Some(Note {
+            date: row.date,
+            time_of_day: serde_plain::from_str::<TimeOfDay>(row.time_of_day).unwrap_or(TimeOfDay::None),
+            category: row.category.to_owned(),
+            detail: row.detail.to_owned(),
+            notes: row.notes.to_owned(),
+        })
+    }
+
+    /// The text searched by `NoteQuery`; journal rows keep their entry in `detail`
+    pub fn text(&self) -> String {
+        if self.category == JOURNAL_CATEGORY {
+            format!("{} {}", self.detail, self.notes)
+        } else {
+            self.notes.to_owned()
+        }
+    }
+}
@@ -1,11 +1,20 @@
-use std::iter::FromIterator;
+use std::{error::Error, fmt::{self, Display}, io::{Cursor, Read}};
 
 use chrono::NaiveDate;
-use serde::Deserialize;
+use serde::Serialize;
 
-use super::{data_manager::DataManager, date_map::BTreeDateMap, symptoms::symptom::Symptom, time_of_day::TimeOfDay};
+use super::{
+    data_manager::{DataManager, DataManagerBuilder},
+    date_format::{DateFormat, DateParseError},
+    schema::{Field, Schema, SchemaError},
+    symptoms::{severity_scale::SeverityScales, symptom::SymptomParseError, symptom_aliases::SymptomAliases},
+};
 
-#[derive(Debug, Deserialize, PartialEq)]
+/// How many rows the date format is detected from
+const DATE_FORMAT_SAMPLE_ROWS: usize = 20;
+
+/// A row as `writer` writes it back out in Bearable's layout, exports are read through `CsvRecord` and their `Schema`
+#[derive(Debug, Serialize, PartialEq)]
 pub struct CsvRow {
     #[serde(with = "bearable_date_format")]
     pub date: NaiveDate,
@@ -23,91 +32,212 @@ pub struct CsvRow {
     pub notes: String,
 }
 
-mod bearable_date_format {
-    use ::regex::Regex;
-    use chrono::NaiveDate;
-    use lazy_static::lazy_static;
-    use serde::{Deserialize, Deserializer};
+/// A row borrowing its fields from the reader's record buffer, so only what's kept gets copied
+#[derive(Debug, PartialEq)]
+pub struct CsvRecord<'a> {
+    pub date: NaiveDate,
+    pub time_of_day: &'a str,
+    pub category: &'a str,
+    pub amount: &'a str,
+    pub detail: &'a str,
+    pub notes: &'a str,
+}
 
-    const FORMAT: &'static str = "%d%b%Y";
+impl<'a> CsvRecord<'a> {
+    /// Reads the fields from wherever the export's schema has them
+    fn read(record: &'a csv::StringRecord, schema: &Schema, date_format: DateFormat) -> Result<CsvRecord<'a>, DateParseError> {
+        Ok(CsvRecord {
+            date: date_format.parse(schema.get(record, Field::Date))?,
+            time_of_day: schema.get(record, Field::TimeOfDay),
+            category: schema.get(record, Field::Category),
+            amount: schema.get(record, Field::Amount),
+            detail: schema.get(record, Field::Detail),
+            notes: schema.get(record, Field::Notes),
+        })
+    }
+}
 
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        lazy_static! {
-            static ref DATE_REGEX: Regex = Regex::new(r"(\d*)(\w*) (\w\w\w) (\d\d\d\d)").unwrap();
+#[derive(Debug)]
+pub enum ParseError {
+    Csv(csv::Error),
+    Schema(SchemaError),
+    /// A date that doesn't parse in the format detected for the export, `line` counts from 1 including the header
+    Date { line: u64, error: DateParseError },
+    /// A symptom row with a time of day or rating that doesn't parse, `line` counted like for `Date`
+    Symptom { line: u64, error: SymptomParseError },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Csv(error) => write!(f, "{}", error),
+            ParseError::Schema(error) => write!(f, "{}", error),
+            ParseError::Date { line, error } => write!(f, "line {}: {}", line, error),
+            ParseError::Symptom { line, error } => write!(f, "line {}: {}", line, error),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::Csv(error) => Some(error),
+            ParseError::Schema(error) => Some(error),
+            ParseError::Date { error, .. } => Some(error),
+            ParseError::Symptom { error, .. } => Some(error),
         }
+    }
+}
 
-        let s = String::deserialize(deserializer)?;
-        let caps = DATE_REGEX.captures(&s).expect("has captures");
+impl From<csv::Error> for ParseError {
+    fn from(error: csv::Error) -> ParseError {
+        ParseError::Csv(error)
+    }
+}
+
+impl From<SchemaError> for ParseError {
+    fn from(error: SchemaError) -> ParseError {
+        ParseError::Schema(error)
+    }
+}
 
-        let day = caps.get(1).unwrap().as_str();
-        let month = caps.get(3).unwrap().as_str();
-        let year = caps.get(4).unwrap().as_str();
+pub mod bearable_date_format {
+    use chrono::{Datelike, NaiveDate};
+    use serde::Serializer;
 
-        let reformatted = format!("{}{}{}", day, month, year);
-        Ok(NaiveDate::parse_from_str(&reformatted, FORMAT).unwrap())
+    pub fn serialize<S>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format(date))
+    }
+
+    /// Formats the way Bearable exports dates, e.g. "8th Dec 2021"
+    pub fn format(date: &NaiveDate) -> String {
+        let suffix = match (date.day() % 10, date.day() % 100) {
+            (_, 11..=13) => "th",
+            (1, _) => "st",
+            (2, _) => "nd",
+            (3, _) => "rd",
+            _ => "th",
+        };
+        format!("{}{} {}", date.day(), suffix, date.format("%b %Y"))
     }
 }
 
-pub fn parse_into_data_manager<R: std::io::Read>(reader: csv::Reader<R>) -> DataManager {
-    let rows = parse_rdr(reader);
-    create_data_manager(rows)
+pub fn parse_into_data_manager<R: Read>(reader: csv::Reader<R>) -> Result<DataManager, ParseError> {
+    stream_into_data_manager(reader, SymptomAliases::default(), SeverityScales::default())
 }
 
-pub fn parse_into_data_manager_str(csv_text: &str) -> DataManager {
-    let rows = parse(csv_text);
-    create_data_manager(rows)
+pub fn parse_into_data_manager_str(csv_text: &str) -> Result<DataManager, ParseError> {
+    parse_into_data_manager(csv::Reader::from_reader(csv_text.as_bytes()))
 }
 
-fn create_data_manager(rows: Vec<CsvRow>) -> DataManager {
-    let symptom_rows = rows.iter().filter(|r| r.category == "Symptom");
-    let mut symptoms = Vec::<Symptom>::new();
-    for symptom_row in symptom_rows {
-        let symptom = Symptom::from(&symptom_row);
+/// Builds the `DataManager` while reading, without collecting every row first.
+/// Aliases and scales are applied like `ChunkedParser::new` does.
+pub fn stream_into_data_manager<R: Read>(mut reader: csv::Reader<R>, aliases: SymptomAliases, scales: SeverityScales) -> Result<DataManager, ParseError> {
+    let schema = Schema::detect(reader.headers()?)?;
+    let sample = read_sample(&mut reader)?;
+    let date_format = detect_date_format(&schema, &sample)?;
+    let mut builder = DataManagerBuilder::default().with_aliases(aliases).with_scales(scales);
+    for record in &sample {
+        add_record(&mut builder, record, &schema, date_format)?;
+    }
+    let mut record = csv::StringRecord::new();
+    while reader.read_record(&mut record)? {
+        add_record(&mut builder, &record, &schema, date_format)?;
+    }
+    Ok(builder.build())
+}
 
-        symptoms.push(symptom);
+/// The first rows, to detect the date format from
+fn read_sample<R: Read>(reader: &mut csv::Reader<R>) -> csv::Result<Vec<csv::StringRecord>> {
+    let mut sample = Vec::new();
+    let mut record = csv::StringRecord::new();
+    while sample.len() < DATE_FORMAT_SAMPLE_ROWS && reader.read_record(&mut record)? {
+        sample.push(record.clone());
     }
-    DataManager::from(symptoms)
+    Ok(sample)
+}
+
+fn detect_date_format(schema: &Schema, sample: &[csv::StringRecord]) -> Result<DateFormat, ParseError> {
+    let dates = sample.iter().map(|record| schema.get(record, Field::Date)).collect::<Vec<&str>>();
+    // Detection only fails when the first row's date doesn't parse
+    DateFormat::detect(&dates).map_err(|error| ParseError::Date { line: 2, error })
 }
 
-fn parse(csv_text: &str) -> Vec<CsvRow> {
-    let reader = csv::Reader::from_reader(csv_text.as_bytes());
-    parse_rdr(reader)
+fn add_record(builder: &mut DataManagerBuilder, record: &csv::StringRecord, schema: &Schema, date_format: DateFormat) -> Result<(), ParseError> {
+    let line = record.position().map(|p| p.line()).unwrap_or_default();
+    let row = CsvRecord::read(record, schema, date_format).map_err(|error| ParseError::Date { line, error })?;
+    builder.add_row(&row).map_err(|error| ParseError::Symptom { line, error })
 }
 
-fn parse_rdr<R: std::io::Read>(mut reader: csv::Reader<R>) -> Vec<CsvRow> {
-    let iter = reader.deserialize::<CsvRow>();
-    Vec::from_iter(iter.map(|s| s.expect("failed parsing line")))
+/// Parses an export a chunk of rows at a time, so a caller can report progress or give up part way
+pub struct ChunkedParser {
+    reader: csv::Reader<Cursor<Vec<u8>>>,
+    total_bytes: u64,
+    schema: Schema,
+    record: csv::StringRecord,
+    date_format: DateFormat,
+    builder: DataManagerBuilder,
+}
+
+impl ChunkedParser {
+    /// Fails when the schema or date format can't be detected from the export's header and first rows
+    pub fn new(csv_text: String, aliases: SymptomAliases, scales: SeverityScales) -> Result<ChunkedParser, ParseError> {
+        let mut sample_reader = csv::Reader::from_reader(csv_text.as_bytes());
+        let schema = Schema::detect(sample_reader.headers()?)?;
+        let date_format = detect_date_format(&schema, &read_sample(&mut sample_reader)?)?;
+        let mut reader = csv::Reader::from_reader(Cursor::new(csv_text.into_bytes()));
+        // Reading the header now keeps it out of the first chunk
+        reader.headers()?;
+        Ok(ChunkedParser {
+            total_bytes: reader.get_ref().get_ref().len() as u64,
+            reader,
+            schema,
+            record: csv::StringRecord::new(),
+            date_format,
+            builder: DataManagerBuilder::default().with_aliases(aliases).with_scales(scales),
+        })
+    }
+
+    /// Parses up to `max_rows` more rows, returns false once the whole export has been read
+    pub fn parse_chunk(&mut self, max_rows: usize) -> Result<bool, ParseError> {
+        for _ in 0..max_rows {
+            if !self.reader.read_record(&mut self.record)? {
+                return Ok(false);
+            }
+            add_record(&mut self.builder, &self.record, &self.schema, self.date_format)?;
+        }
+        Ok(true)
+    }
+
+    /// The export's columns, as detected from its header
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// How much of the export has been read, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        self.reader.position().byte() as f32 / self.total_bytes.max(1) as f32
+    }
+
+    pub fn finish(self) -> DataManager {
+        self.builder.build()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::iter::FromIterator;
+
     use assertables::*;
+    use chrono::Datelike;
     use csv::Reader;
 
-    use super::*;
+    use crate::model::{query::EntryQuery, search::NoteQuery, symptoms::symptom::Symptom, time_of_day::TimeOfDay};
 
-    #[test]
-    fn Parse_ForBearableCsvString_ProducesCsvRow() {
-        let data = r#"date,weekday,time of day,category,rating/amount,detail,notes
-"8th Dec 2021","Wednesday","mid","Symptom","2","Neck pain (Moderate)","""#;
-
-        let result = parse(data);
-
-        let expected_row = CsvRow {
-            date: NaiveDate::from_ymd(2021, 12, 8),
-            weekday: "Wednesday".to_string(),
-            time_of_day: "mid".to_string(),
-            category: "Symptom".to_string(),
-            amount: "2".to_string(),
-            detail: "Neck pain (Moderate)".to_string(),
-            notes: "".to_string(),
-        };
-        assert_eq!(result.len(), 1);
-        assert_eq!(result.first().unwrap(), &expected_row);
-    }
+    use super::*;
 
     #[test]
     fn Parse_ForCsvContainingOnlySymptoms_CorrectlyParsesSymptoms() {
@@ -126,42 +256,48 @@ mod tests {
 "5th Jan 2022","Wednesday","pm","Symptom","4","Back (mid) pain (Unbearable)","""#;
         let date = NaiveDate::from_ymd(2022, 1, 5);
 
-        let data_man = parse_into_data_manager_str(text);
+        let data_man = parse_into_data_manager_str(text).unwrap();
 
         let expected_symptoms = vec!["Headache", "Neck pain", "Back (lower) pain", "Back (mid) pain"];
-        let actual_symptoms = Vec::from_iter(data_man.get_symptom_names().into_iter().map(|s| s as &str));
+        let actual_names = data_man.get_symptom_names(&data_man.get_symptom_ids());
+        let actual_symptoms = Vec::from_iter(actual_names.iter().map(|s| s as &str));
         assert_bag_eq!(expected_symptoms, actual_symptoms);
+        let mid_pain = data_man.get_symptom_id(expected_symptoms[3]).unwrap();
 
         let expected_mid_pain = vec![
             Symptom {
                 date: date,
-                name: "Back (mid) pain".to_string(),
+                id: mid_pain,
                 severity: 1,
                 time_of_day: TimeOfDay::Pre,
+                notes: String::new(),
             },
             Symptom {
                 date: date,
-                name: "Back (mid) pain".to_string(),
+                id: mid_pain,
                 severity: 3,
                 time_of_day: TimeOfDay::AM,
+                notes: String::new(),
             },
             Symptom {
                 date: date,
-                name: "Back (mid) pain".to_string(),
+                id: mid_pain,
                 severity: 3,
                 time_of_day: TimeOfDay::MID,
+                notes: String::new(),
             },
             Symptom {
                 date: date,
-                name: "Back (mid) pain".to_string(),
+                id: mid_pain,
                 severity: 4,
                 time_of_day: TimeOfDay::PM,
+                notes: String::new(),
             },
         ];
         let expected_mid_pain = Vec::from_iter(expected_mid_pain.into_iter());
         let actual_mid_pain = Vec::from_iter(
             data_man
-                .get_all_sorted_symptoms(expected_symptoms[3])
+                .get_all_sorted_symptoms(mid_pain)
                 .unwrap()
                 .into_iter()
                 .map(|s| s.to_owned()),
@@ -169,12 +305,173 @@ mod tests {
         assert_eq!(expected_mid_pain, actual_mid_pain);
     }
 
+    #[test]
+    fn Parse_ForCsvWithNotesAndJournal_MakesNotesSearchable() {
+        let text = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"5th Jan 2022","Wednesday","am","Symptom","2","Headache (Moderate)","After a long drive"
+"5th Jan 2022","Wednesday","pm","Symptom","1","Neck pain (Mild)",""
+"6th Jan 2022","Thursday","","Journal","","Pizza night, long drive home","""#;
+
+        let data_man = parse_into_data_manager_str(text).unwrap();
+
+        let headache = data_man.get_all_sorted_symptoms(data_man.get_symptom_id("Headache").unwrap()).unwrap();
+        assert_eq!(headache[0].notes, "After a long drive");
+        let results = data_man.search_notes(&NoteQuery::parse(r#""long drive""#));
+        assert_eq!(results.iter().map(|n| n.date.day()).collect::<Vec<u32>>(), vec![5, 6]);
+        assert_eq!(data_man.search_notes(&NoteQuery::parse("piz*")).len(), 1);
+    }
+
+    #[test]
+    fn StreamIntoDataManager_ForMixedCategories_MatchesParsingAString() {
+        let text = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"5th Jan 2022","Wednesday","am","Symptom","2","Headache (Moderate)","After a long drive"
+"5th Jan 2022","Wednesday","pm","Symptom","2","Headache (Moderate)",""
+"6th Jan 2022","Thursday","am","Symptom","1","Headache (Mild)",""
+"6th Jan 2022","Thursday","am","Meds/Supplements","1","Ibuprofen",""
+"6th Jan 2022","Thursday","","Journal","","Pizza night","""#;
+
+        let streamed = stream_into_data_manager(Reader::from_reader(text.as_bytes()), SymptomAliases::default(), SeverityScales::default()).unwrap();
+
+        let collected = parse_into_data_manager_str(text).unwrap();
+        let headache = collected.get_symptom_id("Headache").unwrap();
+        assert_eq!(streamed.get_symptom_id("Headache"), Some(headache));
+        assert_eq!(streamed.get_all_sorted_symptoms(headache), collected.get_all_sorted_symptoms(headache));
+        assert_eq!(streamed.get_all_sorted_symptoms(headache).unwrap().len(), 3);
+        assert_eq!(streamed.get_notes(), collected.get_notes());
+        assert_eq!(streamed.get_medications(), collected.get_medications());
+    }
+
+    #[test]
+    fn ChunkedParser_ForOneRowPerChunk_MatchesParsingAtOnce() {
+        let text = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"5th Jan 2022","Wednesday","am","Symptom","2","Headache (Moderate)","After a long drive"
+"5th Jan 2022","Wednesday","pm","Symptom","1","Neck pain (Mild)",""
+"6th Jan 2022","Thursday","am","Meds/Supplements","1","Ibuprofen","""#;
+        let mut parser = ChunkedParser::new(text.to_string(), SymptomAliases::default(), SeverityScales::default()).unwrap();
+
+        let mut progress = vec![parser.progress()];
+        while parser.parse_chunk(1).unwrap() {
+            progress.push(parser.progress());
+        }
+        let data_man = parser.finish();
+
+        assert_eq!(progress.len(), 4);
+        assert!(progress.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(data_man.get_symptom_ids().len(), 2);
+        assert_eq!(data_man.get_medications().len(), 1);
+        // The worker hands the result back serialized
+        let json = serde_json::to_string(&data_man).unwrap();
+        let restored: DataManager = serde_json::from_str(&json).unwrap();
+        let headache = data_man.get_symptom_id("Headache").unwrap();
+        assert_eq!(restored.get_symptom_id("Headache"), Some(headache));
+        assert_eq!(restored.get_all_sorted_symptoms(headache), data_man.get_all_sorted_symptoms(headache));
+        assert_eq!(restored.get_medications(), data_man.get_medications());
+    }
+
+    #[test]
+    fn ParseIntoDataManager_ForFactorsRow_FiltersByEachFactor() {
+        let text = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"8th Jan 2022","Saturday","pm","Factors","","Alcohol | Late night",""
+"8th Jan 2022","Saturday","am","Symptom","3","Headache (Severe)",""
+"9th Jan 2022","Sunday","am","Symptom","2","Headache (Moderate)","""#;
+
+        let data_man = parse_into_data_manager_str(text).unwrap();
+
+        let factors = data_man.get_factors().iter().map(|f| (f.date, f.time_of_day, f.name.as_str())).collect::<Vec<_>>();
+        assert_eq!(factors, vec![
+            (NaiveDate::from_ymd(2022, 1, 8), TimeOfDay::PM, "Alcohol"),
+            (NaiveDate::from_ymd(2022, 1, 8), TimeOfDay::PM, "Late night"),
+        ]);
+        let filtered = data_man.filtered(&EntryQuery::parse("factor:alcohol").unwrap());
+        let headache = filtered.get_all_sorted_symptoms(filtered.get_symptom_id("Headache").unwrap()).unwrap();
+        assert_eq!(headache.iter().map(|s| (s.date, s.severity)).collect::<Vec<_>>(), vec![(NaiveDate::from_ymd(2022, 1, 8), 3)]);
+    }
+
+    #[test]
+    fn ChunkedParser_ForAliasedSymptom_RollsItUpIntoTheCurrentName() {
+        let text = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"5th Jan 2021","Tuesday","am","Symptom","2","Back pain (Moderate)",""
+"5th Jan 2022","Wednesday","am","Symptom","3","Back (lower) pain (Severe)",""
+"5th Jan 2022","Wednesday","pm","Symptom","1","Back (mid) pain (Mild)","""#;
+        let mut aliases = SymptomAliases::default();
+        aliases.set("Back pain", "Back (lower) pain").unwrap();
+        let mut parser = ChunkedParser::new(text.to_string(), aliases, SeverityScales::default()).unwrap();
+
+        while parser.parse_chunk(1).unwrap() {}
+        let data_man = parser.finish();
+
+        let names = data_man.get_symptom_names(&data_man.get_symptom_ids());
+        assert_eq!(names, vec!["Back (lower) pain", "Back (mid) pain"]);
+        let lower_pain = data_man.get_symptom_id("Back (lower) pain").unwrap();
+        let severities = data_man.get_all_sorted_symptoms(lower_pain).unwrap().iter().map(|s| s.severity).collect::<Vec<u8>>();
+        assert_eq!(severities, vec![2, 3]);
+    }
+
+    #[test]
+    fn StreamIntoDataManager_ForIsoAndLocalisedDates_DetectsTheFormat() {
+        let iso = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"2022-01-05","Wednesday","am","Symptom","2","Headache (Moderate)","""#;
+        let german = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"5. Jan. 2022","Mittwoch","am","Symptom","2","Headache (Moderate)","""#;
+        let day_first = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"05/01/2022","Wednesday","am","Symptom","2","Headache (Moderate)","""#;
+
+        for text in [iso, german, day_first] {
+            let data_man = stream_into_data_manager(Reader::from_reader(text.as_bytes()), SymptomAliases::default(), SeverityScales::default()).unwrap();
+            let headache = data_man.get_symptom_id("Headache").unwrap();
+            assert_eq!(data_man.get_all_sorted_symptoms(headache).unwrap()[0].date, NaiveDate::from_ymd(2022, 1, 5));
+        }
+    }
+
+    #[test]
+    fn ChunkedParser_ForDateInAnotherFormat_ReturnsTheLine() {
+        let text = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"5th Jan 2022","Wednesday","am","Symptom","2","Headache (Moderate)",""
+"5th Jan 2022","Wednesday","pm","Symptom","1","Neck pain (Mild)",""
+"2022-01-06","Thursday","am","Symptom","1","Neck pain (Mild)","""#;
+        let mut parser = ChunkedParser::new(text.to_string(), SymptomAliases::default(), SeverityScales::default()).unwrap();
+
+        let error = parser.parse_chunk(10).unwrap_err();
+
+        assert!(matches!(error, ParseError::Date { line: 4, error: DateParseError::UnrecognisedFormat(_) }), "{:?}", error);
+        assert!(ChunkedParser::new("date,detail\n\"someday\",\"Headache\"".to_string(), SymptomAliases::default(), SeverityScales::default()).is_err());
+    }
+
+    #[test]
+    fn ChunkedParser_ForSymptomWithoutRatingOrKnownTimeOfDay_ReturnsTheLine() {
+        let header = "date,weekday,time of day,category,rating/amount,detail,notes\n";
+        let parse = |rows: &str| ChunkedParser::new(format!("{}{}", header, rows), SymptomAliases::default(), SeverityScales::default()).and_then(|mut parser| parser.parse_chunk(10));
+
+        let no_rating = parse("\"5th Jan 2022\",\"Wednesday\",\"am\",\"Symptom\",\"2\",\"Headache (Moderate)\",\"\"\n\"5th Jan 2022\",\"Wednesday\",\"pm\",\"Symptom\",\"\",\"Headache\",\"\"");
+        let evening = parse("\"5th Jan 2022\",\"Wednesday\",\"evening\",\"Symptom\",\"2\",\"Headache (Moderate)\",\"\"");
+
+        assert!(matches!(no_rating, Err(ParseError::Symptom { line: 3, error: SymptomParseError::InvalidAmount(_) })), "{:?}", no_rating);
+        assert!(matches!(evening, Err(ParseError::Symptom { line: 2, error: SymptomParseError::InvalidTimeOfDay(_) })), "{:?}", evening);
+    }
+
+    #[test]
+    fn StreamIntoDataManager_ForOlderSchemaWithReorderedColumns_ReadsByHeader() {
+        let text = r#"category,detail,date,rating,mood
+"Symptom","Headache (Moderate)","5th Jan 2022","2","ok"
+"Symptom","Headache (Severe)","5th Jan 2022","3","ok"
+"Symptom","Headache (Mild)","5th Jan 2022","1","ok""#;
+
+        let data_man = stream_into_data_manager(Reader::from_reader(text.as_bytes()), SymptomAliases::default(), SeverityScales::default()).unwrap();
+
+        let headache = data_man.get_symptom_id("Headache").unwrap();
+        let entries = data_man.get_all_sorted_symptoms(headache).unwrap();
+        assert_eq!((entries[0].date, entries[0].time_of_day, entries[0].severity), (NaiveDate::from_ymd(2022, 1, 5), TimeOfDay::None, 2));
+        assert_eq!(entries.iter().map(|s| s.severity).collect::<Vec<u8>>(), vec![2, 3, 1]);
+        let missing_detail = stream_into_data_manager(Reader::from_reader("date,category,rating\n".as_bytes()), SymptomAliases::default(), SeverityScales::default());
+        assert!(matches!(missing_detail, Err(ParseError::Schema(SchemaError::MissingColumns(_)))));
+    }
+
     #[test]
     fn Parse_ForRealCsv_Works() {
         let mut reader = Reader::from_path(r#"C:\Users\JAK\Downloads\bearable-export-08-01-2022.csv"#).unwrap();
 
-        let data_man = parse_into_data_manager(reader);
+        let data_man = parse_into_data_manager(reader).unwrap();
 
-        assert_eq!(true, data_man.get_symptom_names().len() > 0)
+        assert_eq!(true, data_man.get_symptom_ids().len() > 0)
     }
 }
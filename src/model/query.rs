@@ -0,0 +1,421 @@
+use std::{collections::BTreeSet, error::Error, fmt::{self, Display}, iter::Peekable, str::Chars};
+
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use super::{symptoms::symptom::Symptom, time_of_day::TimeOfDay};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// A symptom entry and what a query can ask about it
+pub struct QueryEntry<'a> {
+    pub symptom: &'a Symptom,
+    pub name: &'a str,
+    /// Lowercase names of the factors logged on the entry's day
+    pub factors: &'a BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    UnexpectedEnd,
+    UnexpectedToken(String),
+    UnterminatedString,
+    UnknownField(String),
+    InvalidValue { field: &'static str, value: String },
+    /// Fields like symptom names can only be compared with `=`, `!=` and `in`
+    UnorderedField(&'static str),
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::UnexpectedEnd => write!(f, "the query ends too early"),
+            QueryError::UnexpectedToken(token) => write!(f, "didn't expect \"{}\" there", token),
+            QueryError::UnterminatedString => write!(f, "a quoted value is missing its closing quote"),
+            QueryError::UnknownField(field) => write!(f, "\"{}\" isn't one of {}", field, Field::ALL.iter().map(Field::name).collect::<Vec<&str>>().join(", ")),
+            QueryError::InvalidValue { field, value } => write!(f, "\"{}\" isn't a {}", value, field),
+            QueryError::UnorderedField(field) => write!(f, "{} can only be compared with =, != or in", field),
+        }
+    }
+}
+
+impl Error for QueryError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Severity,
+    Weekday,
+    TimeOfDay,
+    Date,
+    Symptom,
+}
+
+/// Every field's values compare as one of these, e.g. weekdays as days from Monday
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Value {
+    Number(i64),
+    Text(String),
+}
+
+impl Field {
+    const ALL: [Field; 5] = [Field::Severity, Field::Weekday, Field::TimeOfDay, Field::Date, Field::Symptom];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Field::Severity => "severity",
+            Field::Weekday => "weekday",
+            Field::TimeOfDay => "time",
+            Field::Date => "date",
+            Field::Symptom => "symptom",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Field> {
+        Field::ALL.iter().find(|field| field.name().eq_ignore_ascii_case(name)).copied()
+    }
+
+    fn is_ordered(&self) -> bool {
+        *self != Field::Symptom
+    }
+
+    fn parse_value(&self, text: &str) -> Result<Value, QueryError> {
+        let invalid = || QueryError::InvalidValue { field: self.name(), value: text.to_string() };
+        match self {
+            Field::Severity => text.parse::<u8>().map(|severity| Value::Number(severity as i64)).map_err(|_| invalid()),
+            Field::Weekday => text.parse::<Weekday>().map(|weekday| Value::Number(weekday.num_days_from_monday() as i64)).map_err(|_| invalid()),
+            Field::TimeOfDay => serde_plain::from_str::<TimeOfDay>(&text.to_lowercase()).map(|time_of_day| Value::Number(time_of_day as i64)).map_err(|_| invalid()),
+            Field::Date => NaiveDate::parse_from_str(text, DATE_FORMAT).map(|date| Value::Number(date.num_days_from_ce() as i64)).map_err(|_| invalid()),
+            Field::Symptom => Ok(Value::Text(text.to_lowercase())),
+        }
+    }
+
+    fn value(&self, entry: &QueryEntry) -> Value {
+        match self {
+            Field::Severity => Value::Number(entry.symptom.severity as i64),
+            Field::Weekday => Value::Number(entry.symptom.date.weekday().num_days_from_monday() as i64),
+            Field::TimeOfDay => Value::Number(entry.symptom.time_of_day as i64),
+            Field::Date => Value::Number(entry.symptom.date.num_days_from_ce() as i64),
+            Field::Symptom => Value::Text(entry.name.to_lowercase()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Eq => "=",
+            Comparison::Ne => "!=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+        }
+    }
+
+    fn holds(&self, left: &Value, right: &Value) -> bool {
+        match self {
+            Comparison::Eq => left == right,
+            Comparison::Ne => left != right,
+            Comparison::Lt => left < right,
+            Comparison::Le => left <= right,
+            Comparison::Gt => left > right,
+            Comparison::Ge => left >= right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Field, Comparison, Value),
+    In(Field, Vec<Value>),
+    /// Lowercase factor name
+    Factor(String),
+}
+
+impl Expr {
+    fn matches(&self, entry: &QueryEntry) -> bool {
+        match self {
+            Expr::And(left, right) => left.matches(entry) && right.matches(entry),
+            Expr::Or(left, right) => left.matches(entry) || right.matches(entry),
+            Expr::Not(expr) => !expr.matches(entry),
+            Expr::Compare(field, comparison, value) => comparison.holds(&field.value(entry), value),
+            Expr::In(field, values) => values.contains(&field.value(entry)),
+            Expr::Factor(name) => entry.factors.contains(name),
+        }
+    }
+}
+
+/// Filters symptom entries, e.g. `severity >= 3 and weekday in [Sat, Sun] and factor:alcohol`.
+/// Fields are severity, weekday, time (of day), date (as 2022-01-31) and symptom, combined with
+/// and, or, not and parentheses. Names with spaces go in quotes: `symptom = "Neck pain"`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EntryQuery {
+    expr: Option<Expr>,
+}
+
+impl EntryQuery {
+    pub fn parse(query: &str) -> Result<EntryQuery, QueryError> {
+        let tokens = tokenize(query)?;
+        if tokens.is_empty() {
+            return Ok(EntryQuery::default());
+        }
+        let mut parser = Parser { tokens, position: 0 };
+        let expr = parser.parse_or()?;
+        match parser.next() {
+            Some(token) => Err(QueryError::UnexpectedToken(token.to_string())),
+            None => Ok(EntryQuery { expr: Some(expr) }),
+        }
+    }
+
+    /// An empty query matches every entry
+    pub fn is_empty(&self) -> bool {
+        self.expr.is_none()
+    }
+
+    pub fn matches(&self, entry: &QueryEntry) -> bool {
+        self.expr.iter().all(|expr| expr.matches(entry))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Comparison(Comparison),
+    Open,
+    Close,
+    OpenList,
+    CloseList,
+    Comma,
+    Colon,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "{}", word),
+            Token::Quoted(text) => write!(f, "\"{}\"", text),
+            Token::Comparison(comparison) => write!(f, "{}", comparison.symbol()),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+            Token::OpenList => write!(f, "["),
+            Token::CloseList => write!(f, "]"),
+            Token::Comma => write!(f, ","),
+            Token::Colon => write!(f, ":"),
+        }
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '[' => Token::OpenList,
+            ']' => Token::CloseList,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => text.push(c),
+                        None => return Err(QueryError::UnterminatedString),
+                    }
+                }
+                Token::Quoted(text)
+            }
+            '=' => {
+                skip_if(&mut chars, '=');
+                Token::Comparison(Comparison::Eq)
+            }
+            '!' if skip_if(&mut chars, '=') => Token::Comparison(Comparison::Ne),
+            '<' if skip_if(&mut chars, '=') => Token::Comparison(Comparison::Le),
+            '<' => Token::Comparison(Comparison::Lt),
+            '>' if skip_if(&mut chars, '=') => Token::Comparison(Comparison::Ge),
+            '>' => Token::Comparison(Comparison::Gt),
+            '!' => return Err(QueryError::UnexpectedToken("!".to_string())),
+            c => {
+                let mut word = c.to_string();
+                while let Some(next) = chars.peek().filter(|next| is_word_char(**next)) {
+                    word.push(*next);
+                    chars.next();
+                }
+                Token::Word(word)
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !"()[],:\"=!<>".contains(c)
+}
+
+fn skip_if(chars: &mut Peekable<Chars>, expected: char) -> bool {
+    chars.next_if_eq(&expected).is_some()
+}
+
+/// Recursive descent over the tokens, `or` binds loosest and `not` tightest
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.position), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), QueryError> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(QueryError::UnexpectedToken(token.to_string())),
+            None => Err(QueryError::UnexpectedEnd),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.position += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.parse_not()?;
+        while self.peek_keyword("and") {
+            self.position += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, QueryError> {
+        if self.peek_keyword("not") {
+            self.position += 1;
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_term()
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, QueryError> {
+        let word = match self.next() {
+            Some(Token::Open) => {
+                let expr = self.parse_or()?;
+                self.expect(Token::Close)?;
+                return Ok(expr);
+            }
+            Some(Token::Word(word)) => word,
+            Some(token) => return Err(QueryError::UnexpectedToken(token.to_string())),
+            None => return Err(QueryError::UnexpectedEnd),
+        };
+        if word.eq_ignore_ascii_case("factor") && self.tokens.get(self.position) == Some(&Token::Colon) {
+            self.position += 1;
+            return Ok(Expr::Factor(self.parse_text()?.to_lowercase()));
+        }
+
+        let field = Field::from_name(&word).ok_or(QueryError::UnknownField(word))?;
+        match self.next() {
+            Some(Token::Comparison(comparison)) => {
+                if !field.is_ordered() && !matches!(comparison, Comparison::Eq | Comparison::Ne) {
+                    return Err(QueryError::UnorderedField(field.name()));
+                }
+                Ok(Expr::Compare(field, comparison, field.parse_value(&self.parse_text()?)?))
+            }
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("in") => {
+                self.expect(Token::OpenList)?;
+                let mut values = vec![field.parse_value(&self.parse_text()?)?];
+                while self.tokens.get(self.position) == Some(&Token::Comma) {
+                    self.position += 1;
+                    values.push(field.parse_value(&self.parse_text()?)?);
+                }
+                self.expect(Token::CloseList)?;
+                Ok(Expr::In(field, values))
+            }
+            Some(token) => Err(QueryError::UnexpectedToken(token.to_string())),
+            None => Err(QueryError::UnexpectedEnd),
+        }
+    }
+
+    /// A value, bare or quoted
+    fn parse_text(&mut self) -> Result<String, QueryError> {
+        match self.next() {
+            Some(Token::Word(text)) | Some(Token::Quoted(text)) => Ok(text),
+            Some(token) => Err(QueryError::UnexpectedToken(token.to_string())),
+            None => Err(QueryError::UnexpectedEnd),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::symptoms::symptom_id::SymptomRegistry;
+
+    use super::*;
+
+    fn matches(query: &str, name: &str, date: NaiveDate, time_of_day: TimeOfDay, severity: u8, factors: &[&str]) -> bool {
+        let symptom = Symptom { id: SymptomRegistry::default().intern(name), date, time_of_day, severity, notes: String::new() };
+        let factors = factors.iter().map(|f| f.to_string()).collect::<BTreeSet<String>>();
+        EntryQuery::parse(query).unwrap().matches(&QueryEntry { symptom: &symptom, name, factors: &factors })
+    }
+
+    #[test]
+    fn Matches_ForWeekendQueryWithFactor_ChecksEveryCondition() {
+        let query = "severity >= 3 and weekday in [Sat, Sun] and factor:alcohol";
+        let saturday = NaiveDate::from_ymd(2022, 1, 8);
+        let monday = NaiveDate::from_ymd(2022, 1, 10);
+
+        assert!(matches(query, "Headache", saturday, TimeOfDay::AM, 3, &["alcohol"]));
+        assert!(!matches(query, "Headache", saturday, TimeOfDay::AM, 2, &["alcohol"]));
+        assert!(!matches(query, "Headache", monday, TimeOfDay::AM, 4, &["alcohol"]));
+        assert!(!matches(query, "Headache", saturday, TimeOfDay::AM, 4, &[]));
+    }
+
+    #[test]
+    fn Matches_ForNestedQuery_AppliesPrecedence() {
+        let query = r#"not symptom = "Neck pain" and (time = pm or date < 2022-01-06)"#;
+        let date = NaiveDate::from_ymd(2022, 1, 5);
+
+        assert!(matches(query, "Headache", date, TimeOfDay::AM, 1, &[]));
+        assert!(!matches(query, "Neck pain", date, TimeOfDay::PM, 1, &[]));
+        assert!(matches(query, "Headache", NaiveDate::from_ymd(2022, 1, 7), TimeOfDay::PM, 1, &[]));
+        assert!(!matches(query, "Headache", NaiveDate::from_ymd(2022, 1, 7), TimeOfDay::AM, 1, &[]));
+        assert!(EntryQuery::parse("  ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn Parse_ForMalformedQueries_ReturnsTypedErrors() {
+        assert_eq!(EntryQuery::parse("mood > 3"), Err(QueryError::UnknownField("mood".to_string())));
+        assert_eq!(EntryQuery::parse("severity >="), Err(QueryError::UnexpectedEnd));
+        assert_eq!(EntryQuery::parse("weekday = Caturday"), Err(QueryError::InvalidValue { field: "weekday", value: "Caturday".to_string() }));
+        assert_eq!(EntryQuery::parse("symptom > Headache"), Err(QueryError::UnorderedField("symptom")));
+        assert_eq!(EntryQuery::parse("severity = 3)"), Err(QueryError::UnexpectedToken(")".to_string())));
+        assert_eq!(EntryQuery::parse(r#"symptom = "Neck pain"#), Err(QueryError::UnterminatedString));
+    }
+}
@@ -0,0 +1,266 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use chrono::{Duration, NaiveDate};
+
+use super::{data_manager::DataManager, keywords::HIGH_SEVERITY_THRESHOLD, symptoms::symptom_id::SymptomId};
+
+pub const DEFAULT_REPORT_WEEKS: u32 = 8;
+const TOP_SYMPTOMS: usize = 8;
+/// A medication last taken less than this many days before the report ends may just not have been logged yet
+const MEDICATION_STOP_GRACE_DAYS: i64 = 7;
+
+const SPARKLINE_BAR_WIDTH: usize = 3;
+const SPARKLINE_HEIGHT: usize = 30;
+const SPARKLINE_MAX_SEVERITY: u8 = 4;
+const SEVERITY_COLORS: [&str; 5] = ["#eeeeee", "#fcbba1", "#fb6a4a", "#cb181d", "#67000d"];
+
+const REPORT_STYLE: &str = "
+@page { size: A4; margin: 15mm; }
+body { font: 11px sans-serif; color: #222; max-width: 180mm; margin: auto; }
+h1 { font-size: 18px; margin-bottom: 0; }
+h2 { font-size: 14px; border-bottom: 1px solid #999; margin-top: 16px; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 3px 6px; border-bottom: 1px solid #ddd; }
+tr { page-break-inside: avoid; }
+.period, .footnote { color: #666; }
+";
+
+#[derive(Debug, PartialEq)]
+pub struct ClinicianReport {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Sorted by burden, worst first
+    pub symptoms: Vec<SymptomSummary>,
+    pub medication_changes: Vec<MedicationChange>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SymptomSummary {
+    pub name: String,
+    /// Sum of every severity logged in the period
+    pub burden: u32,
+    pub days_logged: usize,
+    pub flares: usize,
+    /// Worst severity of every day in the period, 0 when nothing was logged
+    pub daily_max: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MedicationChangeKind {
+    Started,
+    Stopped,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MedicationChange {
+    pub date: NaiveDate,
+    pub name: String,
+    pub kind: MedicationChangeKind,
+}
+
+impl ClinicianReport {
+    /// Summarises the last `weeks` weeks up to the most recently logged symptom
+    pub fn build(data_manager: &DataManager, weeks: u32) -> Option<ClinicianReport> {
+        let symptom_ids = data_manager.get_symptom_ids();
+        let end = data_manager.get_symptoms_date_range(&symptom_ids)?.end().date();
+        let start = end - Duration::days(weeks as i64 * 7 - 1);
+
+        let mut symptoms = symptom_ids.iter()
+            .filter_map(|id| SymptomSummary::build(data_manager, *id, start, end))
+            .filter(|summary| summary.days_logged > 0)
+            .collect::<Vec<SymptomSummary>>();
+        symptoms.sort_by(|a, b| b.burden.cmp(&a.burden).then_with(|| a.name.cmp(&b.name)));
+        symptoms.truncate(TOP_SYMPTOMS);
+
+        Some(ClinicianReport {
+            start,
+            end,
+            symptoms,
+            medication_changes: medication_changes(data_manager, start, end),
+        })
+    }
+
+    /// A standalone page with inline styles and SVG, so it can be printed or emailed as is
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        writeln!(html, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">").unwrap();
+        writeln!(html, "<title>Symptom report {} to {}</title>", self.start, self.end).unwrap();
+        writeln!(html, "<style>{}</style>\n</head>\n<body>", REPORT_STYLE).unwrap();
+        writeln!(html, "<h1>Symptom report</h1>").unwrap();
+        writeln!(html, "<p class=\"period\">{} to {}</p>", self.start.format("%-d %b %Y"), self.end.format("%-d %b %Y")).unwrap();
+
+        writeln!(html, "<h2>Symptoms by burden</h2>").unwrap();
+        if self.symptoms.is_empty() {
+            writeln!(html, "<p>No symptoms logged in this period.</p>").unwrap();
+        } else {
+            writeln!(html, "<table>\n<tr><th>Symptom</th><th>Burden</th><th>Days logged</th><th>Flares</th><th>Daily worst severity</th></tr>").unwrap();
+            for symptom in &self.symptoms {
+                writeln!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    escape_html(&symptom.name),
+                    symptom.burden,
+                    symptom.days_logged,
+                    symptom.flares,
+                    sparkline(&symptom.daily_max)
+                )
+                .unwrap();
+            }
+            writeln!(html, "</table>").unwrap();
+        }
+
+        writeln!(html, "<h2>Medication changes</h2>").unwrap();
+        if self.medication_changes.is_empty() {
+            writeln!(html, "<p>No medication changes in this period.</p>").unwrap();
+        } else {
+            writeln!(html, "<table>\n<tr><th>Date</th><th>Change</th><th>Medication</th></tr>").unwrap();
+            for change in &self.medication_changes {
+                let kind = match change.kind {
+                    MedicationChangeKind::Started => "Started",
+                    MedicationChangeKind::Stopped => "Stopped",
+                };
+                writeln!(html, "<tr><td>{}</td><td>{}</td><td>{}</td></tr>", change.date.format("%-d %b %Y"), kind, escape_html(&change.name)).unwrap();
+            }
+            writeln!(html, "</table>").unwrap();
+        }
+
+        writeln!(
+            html,
+            "<p class=\"footnote\">Burden is the sum of all severities logged. A flare is a run of consecutive days reaching Severe or worse.</p>"
+        )
+        .unwrap();
+        writeln!(html, "</body>\n</html>").unwrap();
+        html
+    }
+}
+
+impl SymptomSummary {
+    fn build(data_manager: &DataManager, symptom: SymptomId, start: NaiveDate, end: NaiveDate) -> Option<SymptomSummary> {
+        let daily_max = data_manager.get_daily_max_severities(symptom, ..)?;
+        let daily_sums = data_manager.get_daily_summed_severities(symptom, ..)?;
+        let days = start.iter_days().take_while(|date| *date <= end).collect::<Vec<NaiveDate>>();
+
+        Some(SymptomSummary {
+            name: data_manager.get_symptom_name(symptom).to_string(),
+            burden: daily_sums.range(start..=end).map(|(_, sum)| *sum as u32).sum(),
+            days_logged: daily_max.range(start..=end).count(),
+            flares: count_flares(&daily_max, &days, HIGH_SEVERITY_THRESHOLD),
+            daily_max: days.iter().map(|date| daily_max.get(date).copied().unwrap_or_default()).collect(),
+        })
+    }
+}
+
+/// Counts runs of consecutive days at or above the threshold
+pub fn count_flares(daily_max: &BTreeMap<NaiveDate, u8>, days: &[NaiveDate], threshold: u8) -> usize {
+    let mut flares = 0;
+    let mut in_flare = false;
+    for date in days {
+        let is_high = matches!(daily_max.get(date), Some(severity) if *severity >= threshold);
+        if is_high && !in_flare {
+            flares += 1;
+        }
+        in_flare = is_high;
+    }
+    flares
+}
+
+fn medication_changes(data_manager: &DataManager, start: NaiveDate, end: NaiveDate) -> Vec<MedicationChange> {
+    let mut taken = BTreeMap::<&str, (NaiveDate, NaiveDate)>::new();
+    for medication in data_manager.get_medications() {
+        let (first, last) = taken.entry(medication.name.as_str()).or_insert((medication.date, medication.date));
+        *first = (*first).min(medication.date);
+        *last = (*last).max(medication.date);
+    }
+
+    let stop_cutoff = end - Duration::days(MEDICATION_STOP_GRACE_DAYS);
+    let mut changes = Vec::new();
+    for (name, (first, last)) in taken {
+        if first >= start && first <= end {
+            changes.push(MedicationChange { date: first, name: name.to_string(), kind: MedicationChangeKind::Started });
+        }
+        if last >= start && last < stop_cutoff {
+            changes.push(MedicationChange { date: last, name: name.to_string(), kind: MedicationChangeKind::Stopped });
+        }
+    }
+    changes.sort_by_key(|change| change.date);
+    changes
+}
+
+fn sparkline(daily_max: &[u8]) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+        daily_max.len() * SPARKLINE_BAR_WIDTH,
+        SPARKLINE_HEIGHT
+    );
+    for (i, severity) in daily_max.iter().enumerate() {
+        let clamped = (*severity).min(SPARKLINE_MAX_SEVERITY);
+        let height = (SPARKLINE_HEIGHT * clamped.max(1) as usize) / SPARKLINE_MAX_SEVERITY as usize;
+        write!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            i * SPARKLINE_BAR_WIDTH,
+            SPARKLINE_HEIGHT - height,
+            SPARKLINE_BAR_WIDTH - 1,
+            height,
+            SEVERITY_COLORS[clamped as usize]
+        )
+        .unwrap();
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::parser::parse_into_data_manager_str;
+
+    use super::*;
+
+    const TEXT: &str = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"1st Jan 2022","Saturday","am","Symptom","3","Neck pain (Severe)",""
+"2nd Jan 2022","Sunday","am","Symptom","4","Neck pain (Unbearable)",""
+"3rd Jan 2022","Monday","am","Symptom","1","Neck pain (Mild)",""
+"4th Jan 2022","Tuesday","am","Symptom","3","Neck pain (Severe)",""
+"4th Jan 2022","Tuesday","am","Symptom","1","Headache (Mild)",""
+"1st Jan 2022","Saturday","am","Meds/Supplements","1","Ibuprofen",""
+"2nd Jan 2022","Sunday","am","Meds/Supplements","1","Ibuprofen",""
+"3rd Jan 2022","Monday","am","Meds/Supplements","1","Amitriptyline",""
+"14th Jan 2022","Friday","am","Meds/Supplements","1","Amitriptyline",""
+"14th Jan 2022","Friday","pm","Symptom","1","Headache (Mild)","""#;
+
+    #[test]
+    fn Build_ForTwoWeeks_SummarisesBurdenFlaresAndMedicationChanges() {
+        let data_man = parse_into_data_manager_str(TEXT).unwrap();
+
+        let report = ClinicianReport::build(&data_man, 2).unwrap();
+
+        assert_eq!((report.start, report.end), (NaiveDate::from_ymd(2022, 1, 1), NaiveDate::from_ymd(2022, 1, 14)));
+        let neck_pain = &report.symptoms[0];
+        assert_eq!(neck_pain.name, "Neck pain");
+        assert_eq!((neck_pain.burden, neck_pain.days_logged, neck_pain.flares), (11, 4, 2));
+        assert_eq!(neck_pain.daily_max.len(), 14);
+        assert_eq!(report.symptoms[1].name, "Headache");
+        let changes = report.medication_changes.iter().map(|c| (c.name.as_str(), c.kind)).collect::<Vec<_>>();
+        assert_eq!(changes, vec![
+            ("Ibuprofen", MedicationChangeKind::Started),
+            ("Ibuprofen", MedicationChangeKind::Stopped),
+            ("Amitriptyline", MedicationChangeKind::Started),
+        ]);
+    }
+
+    #[test]
+    fn ToHtml_ForReport_IsSelfContained() {
+        let data_man = parse_into_data_manager_str(TEXT).unwrap();
+
+        let html = ClinicianReport::build(&data_man, 2).unwrap().to_html();
+
+        assert!(html.contains("<svg"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<link"));
+        assert!(html.contains("Neck pain"));
+    }
+}
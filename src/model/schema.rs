@@ -0,0 +1,159 @@
+use std::{error::Error, fmt::{self, Display}};
+
+use serde::{Deserialize, Serialize};
+
+/// The columns of an export the analyzer reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Date,
+    Weekday,
+    TimeOfDay,
+    Category,
+    Amount,
+    Detail,
+    Notes,
+}
+
+impl Field {
+    pub const ALL: [Field; 7] = [Field::Date, Field::Weekday, Field::TimeOfDay, Field::Category, Field::Amount, Field::Detail, Field::Notes];
+
+    /// Rows can't be made sense of without these
+    pub fn is_required(&self) -> bool {
+        matches!(self, Field::Date | Field::Category | Field::Amount | Field::Detail)
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Bearable has renamed and added export columns over time, each version is one set of header names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchemaVersion {
+    /// No weekday column and a plain "rating" column
+    V1,
+    /// Adds "weekday" and renames "rating" to "rating/amount"
+    V2,
+}
+
+/// Header names by version, in `Field::ALL` order, `None` where the version doesn't have the column
+const HEADERS: [(SchemaVersion, [Option<&str>; 7]); 2] = [
+    (SchemaVersion::V2, [Some("date"), Some("weekday"), Some("time of day"), Some("category"), Some("rating/amount"), Some("detail"), Some("notes")]),
+    (SchemaVersion::V1, [Some("date"), None, Some("time of day"), Some("category"), Some("rating"), Some("detail"), Some("notes")]),
+];
+
+impl SchemaVersion {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SchemaVersion::V1 => "v1",
+            SchemaVersion::V2 => "v2",
+        }
+    }
+
+    fn header(&self, field: Field) -> Option<&'static str> {
+        HEADERS.iter().find(|(version, _)| version == self).and_then(|(_, headers)| headers[field.index()])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    /// None of the versions' required columns are all in the header
+    MissingColumns(Vec<&'static str>),
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::MissingColumns(columns) => write!(f, "the export has no {} column", columns.join(", ")),
+        }
+    }
+}
+
+impl Error for SchemaError {}
+
+/// Where each field is in an export's rows, worked out from its header.
+/// Extra and reordered columns are fine, missing optional ones read as empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schema {
+    pub version: SchemaVersion,
+    /// Column indices in `Field::ALL` order
+    columns: Vec<Option<usize>>,
+}
+
+impl Schema {
+    /// Picks the version with the most matching header names, the newest one on a tie
+    pub fn detect<'a>(headers: impl IntoIterator<Item = &'a str>) -> Result<Schema, SchemaError> {
+        let headers = headers.into_iter().map(|h| h.trim().to_lowercase()).collect::<Vec<String>>();
+        let position = |name: &str| headers.iter().position(|h| h == name);
+
+        let (version, _) = HEADERS.iter()
+            .map(|(version, names)| (*version, names.iter().flatten().filter(|name| position(name).is_some()).count()))
+            .fold(None, |best: Option<(SchemaVersion, usize)>, candidate| match best {
+                Some(best) if best.1 >= candidate.1 => Some(best),
+                _ => Some(candidate),
+            })
+            .expect("there are schema versions");
+
+        // A column this version doesn't name may still be there under another version's name
+        let columns = Field::ALL.iter()
+            .map(|field| version.header(*field).and_then(position)
+                .or_else(|| HEADERS.iter().filter_map(|(_, names)| names[field.index()]).find_map(position)))
+            .collect();
+        let schema = Schema { version, columns };
+
+        let missing = schema.missing(true);
+        if !missing.is_empty() {
+            return Err(SchemaError::MissingColumns(missing));
+        }
+        Ok(schema)
+    }
+
+    pub fn get<'a>(&self, record: &'a csv::StringRecord, field: Field) -> &'a str {
+        self.columns[field.index()].and_then(|index| record.get(index)).unwrap_or_default()
+    }
+
+    /// Optional columns the export doesn't have, they read as empty
+    pub fn missing_columns(&self) -> Vec<&'static str> {
+        self.missing(false)
+    }
+
+    fn missing(&self, required: bool) -> Vec<&'static str> {
+        Field::ALL.iter()
+            .filter(|field| field.is_required() == required && self.columns[field.index()].is_none())
+            .filter_map(|field| self.version.header(*field).or_else(|| SchemaVersion::V2.header(*field)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn Detect_ForEachVersionsHeaders_PicksThatVersion() {
+        let v1 = Schema::detect(vec!["date", "time of day", "category", "rating", "detail", "notes"]).unwrap();
+        let v2 = Schema::detect(vec!["date", "weekday", "time of day", "category", "rating/amount", "detail", "notes"]).unwrap();
+
+        assert_eq!(v1.version, SchemaVersion::V1);
+        assert_eq!(v2.version, SchemaVersion::V2);
+        assert!(v2.missing_columns().is_empty());
+    }
+
+    #[test]
+    fn Get_ForReorderedExtraAndMissingColumns_ReadsByHeader() {
+        let schema = Schema::detect(vec!["Detail", "mood", "Category", "Date", "rating/amount"]).unwrap();
+        let record = csv::StringRecord::from(vec!["Neck pain (Mild)", "ok", "Symptom", "8th Dec 2021", "1"]);
+
+        assert_eq!(schema.version, SchemaVersion::V2);
+        assert_eq!(schema.get(&record, Field::Date), "8th Dec 2021");
+        assert_eq!(schema.get(&record, Field::Detail), "Neck pain (Mild)");
+        assert_eq!(schema.get(&record, Field::Amount), "1");
+        assert_eq!(schema.get(&record, Field::Notes), "");
+        assert_eq!(schema.missing_columns(), vec!["weekday", "time of day", "notes"]);
+    }
+
+    #[test]
+    fn Detect_ForHeaderWithoutDetail_ReturnsMissingColumns() {
+        assert_eq!(Schema::detect(vec!["date", "category", "rating/amount", "notes"]), Err(SchemaError::MissingColumns(vec!["detail"])));
+    }
+}
@@ -0,0 +1,106 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Lowercased words of free text, split on anything that isn't a letter, digit or apostrophe
+pub fn tokenize(text: &str) -> Vec<String> {
+    lazy_static! {
+        static ref WORD_REGEX: Regex = Regex::new(r"[\w']+").unwrap();
+    }
+
+    WORD_REGEX
+        .find_iter(text)
+        .map(|m| m.as_str().trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SearchTerm {
+    Word(String),
+    Prefix(String),
+    Phrase(Vec<String>),
+}
+
+/// Case-insensitive query where every term has to match.
+/// `"long drive"` matches the words in order, `piz*` matches any word starting with "piz".
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct NoteQuery {
+    pub terms: Vec<SearchTerm>,
+}
+
+impl NoteQuery {
+    pub fn parse(query: &str) -> NoteQuery {
+        lazy_static! {
+            static ref TERM_REGEX: Regex = Regex::new(r#""([^"]*)"?|(\S+)"#).unwrap();
+        }
+
+        let mut terms = Vec::new();
+        for caps in TERM_REGEX.captures_iter(query) {
+            if let Some(phrase) = caps.get(1) {
+                let words = tokenize(phrase.as_str());
+                match words.len() {
+                    0 => {}
+                    1 => terms.push(SearchTerm::Word(words[0].to_owned())),
+                    _ => terms.push(SearchTerm::Phrase(words)),
+                }
+            } else if let Some(word) = caps.get(2) {
+                let is_prefix = word.as_str().ends_with('*');
+                for token in tokenize(word.as_str()) {
+                    terms.push(if is_prefix { SearchTerm::Prefix(token) } else { SearchTerm::Word(token) });
+                }
+            }
+        }
+        NoteQuery { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        let words = tokenize(text);
+        self.terms.iter().all(|term| match term {
+            SearchTerm::Word(word) => words.iter().any(|w| w == word),
+            SearchTerm::Prefix(prefix) => words.iter().any(|w| w.starts_with(prefix.as_str())),
+            SearchTerm::Phrase(phrase) => words.windows(phrase.len()).any(|window| window == phrase.as_slice()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn Parse_ForQuotedPhraseAndPrefix_ProducesTerms() {
+        let query = NoteQuery::parse(r#"Pizza "Long  drive" stretch*"#);
+
+        let expected = vec![
+            SearchTerm::Word("pizza".to_string()),
+            SearchTerm::Phrase(vec!["long".to_string(), "drive".to_string()]),
+            SearchTerm::Prefix("stretch".to_string()),
+        ];
+        assert_eq!(query.terms, expected);
+    }
+
+    #[test]
+    fn Matches_ForPhrase_RequiresWordsInOrder() {
+        let query = NoteQuery::parse(r#""long drive""#);
+
+        assert!(query.matches("After a LONG drive to the coast"));
+        assert!(!query.matches("drive was long"));
+    }
+
+    #[test]
+    fn Matches_ForPrefixAndWord_IsCaseInsensitiveAndRequiresAllTerms() {
+        let query = NoteQuery::parse("stretch* neck");
+
+        assert!(query.matches("Stretching helped my neck"));
+        assert!(!query.matches("Stretching helped my back"));
+        assert!(!query.matches("necklace, no stretching"));
+    }
+}
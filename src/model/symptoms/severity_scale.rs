@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Bearable's own severities, 0 is a symptom logged as absent
+const BEARABLE_LABELS: [&str; 5] = ["", "Mild", "Moderate", "Severe", "Unbearable"];
+/// The scale values that don't fit Bearable's are assumed to be on, e.g. pain scores
+const NUMERIC_SCALE_MAX: u8 = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeverityLevel {
+    pub value: u8,
+    pub label: String,
+}
+
+/// The range a symptom is rated on, with names for some or all of the values, e.g. 1-5 "Awful" to "Great"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeverityScale {
+    pub min: u8,
+    pub max: u8,
+    pub levels: Vec<SeverityLevel>,
+}
+
+impl Default for SeverityScale {
+    fn default() -> SeverityScale {
+        SeverityScale::with_labels(0, 4, &BEARABLE_LABELS.join(",")).expect("Bearable's labels fit its scale")
+    }
+}
+
+impl SeverityScale {
+    pub fn numeric(min: u8, max: u8) -> SeverityScale {
+        SeverityScale { min, max, levels: Vec::new() }
+    }
+
+    /// `labels` names the values from `min` up, separated by commas, blank ones stay unnamed
+    pub fn with_labels(min: u8, max: u8, labels: &str) -> Result<SeverityScale, String> {
+        if min >= max {
+            return Err(format!("The scale has to go up from {}", min));
+        }
+        let labels = labels.split(',').map(str::trim).collect::<Vec<&str>>();
+        if labels.len() > (max - min) as usize + 1 {
+            return Err(format!("{} labels don't fit a scale from {} to {}", labels.len(), min, max));
+        }
+        let levels = (min..=max).zip(labels)
+            .filter(|(_, label)| !label.is_empty())
+            .map(|(value, label)| SeverityLevel { value, label: label.to_string() })
+            .collect();
+        Ok(SeverityScale { min, max, levels })
+    }
+
+    /// The scale for a symptom nobody set one for, Bearable's unless the values go above it
+    pub fn infer(max_value: u8) -> SeverityScale {
+        let bearable = SeverityScale::default();
+        if max_value <= bearable.max {
+            bearable
+        } else {
+            SeverityScale::numeric(0, max_value.max(NUMERIC_SCALE_MAX))
+        }
+    }
+
+    pub fn label(&self, value: u8) -> Option<&str> {
+        self.levels.iter().find(|level| level.value == value).map(|level| level.label.as_str())
+    }
+
+    /// The value a label names, e.g. 3 for "Severe" on Bearable's scale
+    pub fn value(&self, label: &str) -> Option<u8> {
+        self.levels.iter().find(|level| level.label == label).map(|level| level.value)
+    }
+
+    /// The labels as `with_labels` takes them
+    pub fn labels_text(&self) -> String {
+        (self.min..=self.max).map(|value| self.label(value).unwrap_or_default()).collect::<Vec<&str>>().join(",")
+    }
+}
+
+/// Scales set for symptoms by name, the rest get `SeverityScale::infer`red
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SeverityScales {
+    scales: BTreeMap<String, SeverityScale>,
+}
+
+impl SeverityScales {
+    pub fn set(&mut self, symptom_name: &str, scale: SeverityScale) {
+        self.scales.insert(symptom_name.to_string(), scale);
+    }
+
+    pub fn remove(&mut self, symptom_name: &str) {
+        self.scales.remove(symptom_name);
+    }
+
+    pub fn get(&self, symptom_name: &str) -> Option<&SeverityScale> {
+        self.scales.get(symptom_name)
+    }
+}
+
+pub fn to_json(scales: &SeverityScales) -> serde_json::Result<String> {
+    serde_json::to_string(scales)
+}
+
+pub fn from_json(json: &str) -> serde_json::Result<SeverityScales> {
+    serde_json::from_str(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn WithLabels_ForMoodScale_NamesValuesFromMin() {
+        let mood = SeverityScale::with_labels(1, 5, "Awful, Bad,, Good, Great").unwrap();
+
+        assert_eq!(mood.label(1), Some("Awful"));
+        assert_eq!(mood.label(3), None);
+        assert_eq!(mood.label(5), Some("Great"));
+        assert_eq!(mood.value("Good"), Some(4));
+        assert_eq!(mood.labels_text(), "Awful,Bad,,Good,Great");
+        assert!(SeverityScale::with_labels(1, 2, "a,b,c").is_err());
+        assert!(SeverityScale::with_labels(5, 5, "").is_err());
+    }
+
+    #[test]
+    fn Infer_ForValuesAboveBearablesScale_UsesNumericScale() {
+        assert_eq!(SeverityScale::infer(3).label(4), Some("Unbearable"));
+        assert_eq!(SeverityScale::infer(7), SeverityScale::numeric(0, 10));
+        assert_eq!(SeverityScale::infer(12).max, 12);
+    }
+}
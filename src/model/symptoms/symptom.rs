@@ -1,19 +1,46 @@
-use std::fmt::Display;
+use std::{error::Error, fmt::{self, Display}};
 
 use chrono::{NaiveDate, NaiveDateTime};
 use lazy_static::lazy_static;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use timespan::NaiveDateTimeSpan;
 
-use crate::model::{date_map::OrderedNaiveDateTimeSpan, parser::CsvRow};
+use crate::model::{date_map::OrderedNaiveDateTimeSpan, parser::{CsvRecord, CsvRow}};
 
-use super::super::time_of_day::TimeOfDay;
+use super::{super::time_of_day::TimeOfDay, severity_scale::SeverityScale, symptom_id::SymptomId};
 
-#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
+pub const SYMPTOM_CATEGORY: &str = "Symptom";
+
+lazy_static! {
+    /// A detail's name and the severity label Bearable appends to it
+    static ref DETAIL_REGEX: Regex = Regex::new(r"^(.*) \((Mild|Moderate|Severe|Unbearable|\d+)\)$").unwrap();
+    /// A detail ending in any label in brackets, which might be one from a scale set for the symptom
+    static ref LABELLED_DETAIL_REGEX: Regex = Regex::new(r"^(.*) \(([^()]+)\)$").unwrap();
+}
+
+/// A symptom row whose time of day or rating can't be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymptomParseError {
+    InvalidTimeOfDay(String),
+    /// Not a rating from 0 to 255, e.g. empty or "mild"
+    InvalidAmount(String),
+}
+
+impl Display for SymptomParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymptomParseError::InvalidTimeOfDay(time_of_day) => write!(f, "\"{}\" isn't a time of day we know", time_of_day),
+            SymptomParseError::InvalidAmount(amount) => write!(f, "\"{}\" isn't a symptom rating", amount),
+        }
+    }
+}
+
+impl Error for SymptomParseError {}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 pub struct Symptom {
-    #[serde(rename = "detail")]
-    pub name: String,
+    pub id: SymptomId,
 
     pub date: NaiveDate,
 
@@ -22,15 +49,38 @@ pub struct Symptom {
 
     #[serde(rename = "rating/amount")]
     pub severity: u8,
+
+    #[serde(default)]
+    pub notes: String,
 }
 
 impl Symptom {
-    pub fn from(row: &CsvRow) -> Symptom {
-        Symptom {
-            name: Symptom::parse_name(&row.detail),
-            time_of_day: serde_plain::from_str::<TimeOfDay>(&row.time_of_day).unwrap(),
-            severity: str::parse::<u8>(&row.amount).expect("Failed to parse symptom amount"),
+    /// `id` stands for the name parsed out of `row.detail`
+    pub fn from(row: &CsvRecord, id: SymptomId) -> Result<Symptom, SymptomParseError> {
+        Ok(Symptom {
+            id,
+            time_of_day: serde_plain::from_str::<TimeOfDay>(row.time_of_day)
+                .map_err(|_| SymptomParseError::InvalidTimeOfDay(row.time_of_day.to_string()))?,
+            severity: row.amount.trim().parse::<u8>().map_err(|_| SymptomParseError::InvalidAmount(row.amount.to_string()))?,
             date: row.date,
+            notes: row.notes.to_owned(),
+        })
+    }
+
+    /// The row Bearable would have exported for this entry, labelled on the symptom's scale
+    pub fn to_row(&self, name: &str, scale: &SeverityScale) -> CsvRow {
+        let detail = match scale.label(self.severity) {
+            Some(label) => format!("{} ({})", name, label),
+            None => name.to_owned(),
+        };
+        CsvRow {
+            date: self.date,
+            weekday: self.date.format("%A").to_string(),
+            time_of_day: serde_plain::to_string(&self.time_of_day).unwrap(),
+            category: SYMPTOM_CATEGORY.to_string(),
+            amount: self.severity.to_string(),
+            detail,
+            notes: self.notes.to_owned(),
         }
     }
 
@@ -40,13 +90,22 @@ impl Symptom {
         Ok(OrderedNaiveDateTimeSpan(date_time_span))
     }
 
-    fn parse_name(name: &str) -> String {
-        lazy_static! {
-            static ref NAME_REGEX: Regex = Regex::new(r"(.*) (\(Mild\)|\(Moderate\)|\(Severe\)|\(Unbearable\))").unwrap();
+    /// "Neck pain (Mild)" and "Pain score (7)" become "Neck pain" and "Pain score", details without a severity are kept whole
+    pub fn parse_name(name: &str) -> String {
+        match DETAIL_REGEX.captures(name).and_then(|caps| caps.get(1)) {
+            Some(name) => name.as_str().to_string(),
+            None => name.to_string(),
         }
+    }
 
-        let caps = NAME_REGEX.captures(name).expect("name contains severity");
+    /// "Mood (Great)" becomes "Mood" and "Great", whether or not "Great" is a label on the symptom's scale
+    pub fn split_label(detail: &str) -> Option<(&str, &str)> {
+        let caps = LABELLED_DETAIL_REGEX.captures(detail)?;
+        Some((caps.get(1)?.as_str(), caps.get(2)?.as_str()))
+    }
 
-        caps.get(1).unwrap().as_str().to_string()
+    /// "Neck pain (Mild)" and "Pain score (7)" become "Mild" and "7"
+    pub fn parse_severity_label(detail: &str) -> Option<&str> {
+        DETAIL_REGEX.captures(detail).and_then(|caps| caps.get(2)).map(|label| label.as_str())
     }
 }
@@ -0,0 +1,99 @@
+use std::{collections::BTreeMap, iter};
+
+use serde::{Deserialize, Serialize};
+
+/// Old symptom names and the current names they roll up into, e.g. "Back pain" -> "Back (lower) pain".
+/// Renames can chain, an alias may point at a name that has since been renamed again.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SymptomAliases {
+    aliases: BTreeMap<String, String>,
+}
+
+impl SymptomAliases {
+    /// Rolls `alias` up into `name`, refusing aliases that would end up pointing back at themselves
+    pub fn set(&mut self, alias: &str, name: &str) -> Result<(), String> {
+        let (alias, name) = (alias.trim(), name.trim());
+        if alias.is_empty() || name.is_empty() {
+            return Err("Both the old and the current symptom name are needed".to_string());
+        }
+        if self.chain(name).any(|step| step == alias) {
+            return Err(format!("\"{}\" already rolls up into \"{}\"", name, alias));
+        }
+        self.aliases.insert(alias.to_string(), name.to_string());
+        Ok(())
+    }
+
+    pub fn remove(&mut self, alias: &str) {
+        self.aliases.remove(alias);
+    }
+
+    /// The current name for a symptom, following renames until one isn't an alias
+    pub fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        self.chain(name).last().unwrap_or(name)
+    }
+
+    /// `name` and every name it renames through, stopping short of going round a cycle
+    /// that `set` would have refused but could still come in through `from_json`
+    fn chain<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        iter::successors(Some(name), move |name| self.aliases.get(*name).map(String::as_str)).take(self.aliases.len() + 1)
+    }
+
+    /// Every alias and the name it was set to, alphabetically by alias
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.aliases.iter().map(|(alias, name)| (alias.as_str(), name.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+}
+
+pub fn to_json(aliases: &SymptomAliases) -> serde_json::Result<String> {
+    serde_json::to_string(aliases)
+}
+
+pub fn from_json(json: &str) -> serde_json::Result<SymptomAliases> {
+    serde_json::from_str(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn Resolve_ForChainedRenames_ReturnsTheCurrentName() {
+        let mut aliases = SymptomAliases::default();
+        aliases.set("Back pain", "Back (lower) pain").unwrap();
+        aliases.set("Back (lower) pain", "Lower back pain").unwrap();
+
+        assert_eq!(aliases.resolve("Back pain"), "Lower back pain");
+        assert_eq!(aliases.resolve("Headache"), "Headache");
+        assert_eq!(from_json(&to_json(&aliases).unwrap()).unwrap(), aliases);
+    }
+
+    #[test]
+    fn Set_ForAliasLeadingBackToItself_IsRefused() {
+        let mut aliases = SymptomAliases::default();
+        aliases.set("Back pain", "Back (lower) pain").unwrap();
+
+        assert!(aliases.set("Back (lower) pain", "Back pain").is_err());
+        assert!(aliases.set("Headache", "Headache").is_err());
+        assert_eq!(aliases.iter().collect::<Vec<_>>(), vec![("Back pain", "Back (lower) pain")]);
+    }
+
+    #[test]
+    fn Set_ForRepointedAliasClosingACycle_IsRefused() {
+        let mut aliases = SymptomAliases::default();
+        aliases.set("A", "B").unwrap();
+        aliases.set("B", "C").unwrap();
+
+        assert!(aliases.set("B", "A").is_err());
+        assert!(aliases.set("A", "A").is_err());
+        assert!(aliases.set("B", "D").is_ok());
+        assert_eq!(aliases.resolve("A"), "D");
+
+        let cyclic = from_json(r#"{"A":"B","B":"A"}"#).unwrap();
+        assert!(["A", "B"].contains(&cyclic.resolve("A")));
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+/// How the members' severities logged in the same time of day are combined into the group's severity
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupCombination {
+    #[default]
+    Max,
+    Sum,
+}
+
+impl GroupCombination {
+    pub const ALL: [GroupCombination; 2] = [GroupCombination::Max, GroupCombination::Sum];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            GroupCombination::Max => "max",
+            GroupCombination::Sum => "sum",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GroupCombination::Max => "Worst of the group",
+            GroupCombination::Sum => "Sum of the group",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<GroupCombination> {
+        GroupCombination::ALL.iter().find(|combination| combination.id() == id).copied()
+    }
+
+    pub fn combine(&self, severities: impl Iterator<Item = u8>) -> u8 {
+        match self {
+            GroupCombination::Max => severities.max().unwrap_or_default(),
+            GroupCombination::Sum => severities.fold(0, u8::saturating_add),
+        }
+    }
+}
+
+/// Symptoms charted and analysed together as if they were one, e.g. "Musculoskeletal"
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymptomGroup {
+    pub name: String,
+    pub members: Vec<String>,
+    pub combination: GroupCombination,
+}
+
+/// Adds the group, replacing any saved one with the same name
+pub fn save_group(groups: &mut Vec<SymptomGroup>, group: SymptomGroup) {
+    match groups.iter_mut().find(|g| g.name == group.name) {
+        Some(existing) => *existing = group,
+        None => {
+            groups.push(group);
+            groups.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+    }
+}
+
+pub fn to_json(groups: &[SymptomGroup]) -> serde_json::Result<String> {
+    serde_json::to_string(groups)
+}
+
+pub fn from_json(json: &str) -> serde_json::Result<Vec<SymptomGroup>> {
+    serde_json::from_str(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn Combine_ForEachCombination_TakesMaxOrSaturatingSum() {
+        assert_eq!(GroupCombination::Max.combine(vec![1, 3, 2].into_iter()), 3);
+        assert_eq!(GroupCombination::Sum.combine(vec![1, 3, 2].into_iter()), 6);
+        assert_eq!(GroupCombination::Sum.combine(vec![200, 100].into_iter()), u8::MAX);
+        assert_eq!(GroupCombination::from_id("sum"), Some(GroupCombination::Sum));
+    }
+}
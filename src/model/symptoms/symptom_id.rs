@@ -0,0 +1,118 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Stands in for a symptom name, only meaningful together with the `SymptomRegistry` that handed it out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SymptomId(u32);
+
+impl SymptomId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Interns symptom names, so entries carry a copyable id instead of their own copy of the name.
+/// Ids are handed out in the order names are first seen and never change afterwards.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SymptomRegistry {
+    names: Vec<String>,
+    ids: HashMap<String, SymptomId>,
+}
+
+impl SymptomRegistry {
+    pub fn from_names(names: Vec<String>) -> SymptomRegistry {
+        let mut registry = SymptomRegistry::default();
+        for name in names {
+            registry.intern(&name);
+        }
+        registry
+    }
+
+    pub fn intern(&mut self, name: &str) -> SymptomId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = SymptomId(self.names.len() as u32);
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<SymptomId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: SymptomId) -> &str {
+        &self.names[id.index()]
+    }
+
+    /// Forgets every name interned after the first `len`
+    pub fn truncate(&mut self, len: usize) {
+        for name in self.names.drain(len.min(self.names.len())..) {
+            self.ids.remove(&name);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Every id, alphabetically by name ignoring case, so lists come out the same on every load
+    pub fn sorted_ids(&self) -> Vec<SymptomId> {
+        let mut ids = (0..self.names.len() as u32).map(SymptomId).collect::<Vec<SymptomId>>();
+        ids.sort_by(|a, b| self.compare(*a, *b));
+        ids
+    }
+
+    /// Alphabetical by name ignoring case
+    pub fn compare(&self, a: SymptomId, b: SymptomId) -> Ordering {
+        let (a, b) = (self.name(a), self.name(b));
+        a.to_lowercase().cmp(&b.to_lowercase()).then_with(|| a.cmp(b))
+    }
+}
+
+/// Stored as the names in id order, which is all that's needed to hand out the same ids again
+impl Serialize for SymptomRegistry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.names.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SymptomRegistry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<SymptomRegistry, D::Error> {
+        Ok(SymptomRegistry::from_names(Vec::<String>::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn Intern_ForRepeatedName_ReturnsTheSameId() {
+        let mut registry = SymptomRegistry::default();
+
+        let neck_pain = registry.intern("Neck pain");
+        let headache = registry.intern("Headache");
+
+        assert_eq!(registry.intern("Neck pain"), neck_pain);
+        assert_ne!(headache, neck_pain);
+        assert_eq!(registry.name(headache), "Headache");
+        assert_eq!(registry.id("Headache"), Some(headache));
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn SortedIds_ForNamesInAnyOrder_AreAlphabeticalIgnoringCase() {
+        let registry = SymptomRegistry::from_names(vec!["neck pain".to_string(), "Headache".to_string(), "Back pain".to_string()]);
+
+        let names = registry.sorted_ids().into_iter().map(|id| registry.name(id)).collect::<Vec<&str>>();
+
+        assert_eq!(names, vec!["Back pain", "Headache", "neck pain"]);
+    }
+}
@@ -0,0 +1,98 @@
+use chrono::{NaiveDateTime, NaiveTime};
+use serde::Serialize;
+
+use super::{data_manager::DataManager, notes::note::JOURNAL_CATEGORY, symptoms::symptom::SYMPTOM_CATEGORY, time_of_day::TimeOfDay};
+
+pub const SEVERITY_UNIT: &str = "severity";
+
+/// One observation per row, the "tidy" long format R and pandas expect
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Observation {
+    pub timestamp: NaiveDateTime,
+    pub category: String,
+    pub name: String,
+    pub value: Option<u8>,
+    pub unit: String,
+    pub time_of_day: TimeOfDay,
+    pub notes: String,
+}
+
+/// Every symptom entry with its name already split from the severity label, plus the journal entries, in time order
+pub fn observations(data_manager: &DataManager) -> Vec<Observation> {
+    let symptoms = data_manager.get_symptom_ids().into_iter()
+        .filter_map(|id| data_manager.get_all_sorted_symptoms(id))
+        .flatten()
+        .map(|symptom| Observation {
+            timestamp: timestamp(symptom.date.and_hms(0, 0, 0), symptom.time_of_day),
+            category: SYMPTOM_CATEGORY.to_string(),
+            name: data_manager.get_symptom_name(symptom.id).to_string(),
+            value: Some(symptom.severity),
+            unit: SEVERITY_UNIT.to_string(),
+            time_of_day: symptom.time_of_day,
+            notes: symptom.notes.to_owned(),
+        });
+    let journal = data_manager.get_notes()
+        .iter()
+        .filter(|note| note.category == JOURNAL_CATEGORY)
+        .map(|note| Observation {
+            timestamp: timestamp(note.date.and_hms(0, 0, 0), note.time_of_day),
+            category: note.category.to_owned(),
+            name: String::new(),
+            value: None,
+            unit: String::new(),
+            time_of_day: note.time_of_day,
+            notes: note.text().trim().to_string(),
+        });
+
+    let mut observations = symptoms.chain(journal).collect::<Vec<Observation>>();
+    observations.sort_by_key(|observation| observation.timestamp);
+    observations
+}
+
+pub fn to_tidy_csv_string(observations: &[Observation]) -> csv::Result<String> {
+    let mut buffer = Vec::new();
+    let mut writer = csv::Writer::from_writer(&mut buffer);
+    for observation in observations {
+        writer.serialize(observation)?;
+    }
+    writer.flush()?;
+    drop(writer);
+    Ok(String::from_utf8(buffer).expect("csv writer only writes the UTF-8 it was given"))
+}
+
+pub fn to_tidy_json_string(observations: &[Observation]) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(observations)
+}
+
+/// Entries are stamped with the start of their time of day slot, or midnight when Bearable didn't record one
+fn timestamp(midnight: NaiveDateTime, time_of_day: TimeOfDay) -> NaiveDateTime {
+    match time_of_day {
+        TimeOfDay::None => midnight,
+        time_of_day => midnight.date().and_time(time_of_day.span().map(|span| span.start).unwrap_or_else(|_| NaiveTime::from_hms(0, 0, 0))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::parser::parse_into_data_manager_str;
+
+    use super::*;
+
+    #[test]
+    fn ToTidyCsvString_ForSymptomsAndJournal_WritesOneRowPerObservation() {
+        let text = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"5th Jan 2022","Wednesday","pm","Symptom","2","Neck pain (Moderate)","stiff"
+"5th Jan 2022","Wednesday","am","Symptom","1","Back (lower) pain (Mild)",""
+"5th Jan 2022","Wednesday","","Journal","","Long drive","""#;
+        let data_man = parse_into_data_manager_str(text).unwrap();
+
+        let csv = to_tidy_csv_string(&observations(&data_man)).unwrap();
+
+        let expected = "timestamp,category,name,value,unit,time_of_day,notes
+2022-01-05T00:00:00,Journal,,,,,Long drive
+2022-01-05T06:00:00,Symptom,Back (lower) pain,1,severity,am,
+2022-01-05T18:00:00,Symptom,Neck pain,2,severity,pm,stiff
+";
+        assert_eq!(csv, expected);
+    }
+}
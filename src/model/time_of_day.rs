@@ -1,10 +1,10 @@
 use std::str::FromStr;
 
 use chrono::{Duration, NaiveDateTime, NaiveTime};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use timespan::NaiveTimeSpan;
 
-#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum TimeOfDay {
     #[serde(rename = "")]
@@ -27,8 +27,8 @@ impl TimeOfDay {
             TimeOfDay::AM => span(NaiveTime::from_hms(6, 0, 0)),
             TimeOfDay::MID => span(NaiveTime::from_hms(12, 0, 0)),
             TimeOfDay::PM => span(NaiveTime::from_hms(18, 0, 0)),
-            TimeOfDay::None => todo!(),
-            TimeOfDay::AllDay => NaiveTimeSpan::new(NaiveTime::from_hms(0, 0, 0), NaiveTime::from_hms(23, 59, 59)),
+            // Exports without a time of day column only tell us the day
+            TimeOfDay::None | TimeOfDay::AllDay => NaiveTimeSpan::new(NaiveTime::from_hms(0, 0, 0), NaiveTime::from_hms(23, 59, 59)),
         }
     }
 }
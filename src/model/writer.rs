@@ -0,0 +1,97 @@
+use std::{io, ops::RangeInclusive};
+
+use chrono::NaiveDate;
+
+use super::{data_manager::DataManager, symptoms::{symptom::Symptom, symptom_id::SymptomId}};
+
+/// Bearable leaves the header unquoted but quotes every value
+const BEARABLE_HEADER: &str = "date,weekday,time of day,category,rating/amount,detail,notes\n";
+
+/// Writes the selected symptoms within the dates in the same layout as a Bearable export,
+/// so the result can be loaded back in here or handed to anyone expecting Bearable's CSV
+pub fn write_bearable_csv<W: io::Write>(data_manager: &DataManager, symptom_ids: &[SymptomId], dates: RangeInclusive<NaiveDate>, mut writer: W) -> csv::Result<()> {
+    let mut symptoms = symptom_ids.iter()
+        .filter_map(|id| data_manager.get_all_sorted_symptoms(*id))
+        .flatten()
+        .filter(|symptom| dates.contains(&symptom.date))
+        .collect::<Vec<&Symptom>>();
+    symptoms.sort_by_key(|symptom| (symptom.date, symptom.date_time_span().map(|span| span.start).ok()));
+
+    writer.write_all(BEARABLE_HEADER.as_bytes())?;
+    let mut csv_writer = csv::WriterBuilder::new().has_headers(false).quote_style(csv::QuoteStyle::Always).from_writer(writer);
+    for symptom in symptoms {
+        csv_writer.serialize(symptom.to_row(data_manager.get_symptom_name(symptom.id), &data_manager.get_severity_scale(symptom.id)))?;
+    }
+    csv_writer.flush()?;
+    Ok(())
+}
+
+pub fn to_bearable_csv_string(data_manager: &DataManager, symptom_ids: &[SymptomId], dates: RangeInclusive<NaiveDate>) -> csv::Result<String> {
+    let mut buffer = Vec::new();
+    write_bearable_csv(data_manager, symptom_ids, dates, &mut buffer)?;
+    Ok(String::from_utf8(buffer).expect("csv writer only writes the UTF-8 it was given"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{
+        parser::{bearable_date_format, parse_into_data_manager_str, ChunkedParser},
+        symptoms::{severity_scale::{SeverityScale, SeverityScales}, symptom_aliases::SymptomAliases},
+    };
+
+    use super::*;
+
+    #[test]
+    fn Format_ForVariousDays_UsesOrdinalSuffixes() {
+        let formatted = [1, 2, 3, 4, 11, 12, 13, 21, 22, 23, 31]
+            .iter()
+            .map(|day| bearable_date_format::format(&NaiveDate::from_ymd(2021, 12, *day)))
+            .collect::<Vec<String>>();
+
+        assert_eq!(formatted, vec![
+            "1st Dec 2021", "2nd Dec 2021", "3rd Dec 2021", "4th Dec 2021", "11th Dec 2021", "12th Dec 2021",
+            "13th Dec 2021", "21st Dec 2021", "22nd Dec 2021", "23rd Dec 2021", "31st Dec 2021",
+        ]);
+    }
+
+    #[test]
+    fn ToBearableCsvString_ForSelectedSymptomAndDates_RoundTripsThroughParser() {
+        let text = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"8th Dec 2021","Wednesday","mid","Symptom","2","Neck pain (Moderate)","stiff, after a ""long"" drive"
+"8th Dec 2021","Wednesday","am","Symptom","1","Headache (Mild)",""
+"9th Dec 2021","Thursday","pre","Symptom","4","Neck pain (Unbearable)",""
+"10th Dec 2021","Friday","pm","Symptom","1","Neck pain (Mild)","""#;
+        let data_man = parse_into_data_manager_str(text).unwrap();
+        let neck_pain = data_man.get_symptom_id("Neck pain").unwrap();
+
+        let exported = to_bearable_csv_string(&data_man, &[neck_pain], NaiveDate::from_ymd(2021, 12, 8)..=NaiveDate::from_ymd(2021, 12, 9)).unwrap();
+
+        let expected = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"8th Dec 2021","Wednesday","mid","Symptom","2","Neck pain (Moderate)","stiff, after a ""long"" drive"
+"9th Dec 2021","Thursday","pre","Symptom","4","Neck pain (Unbearable)",""
+"#;
+        assert_eq!(exported, expected);
+        let reparsed = parse_into_data_manager_str(&exported).unwrap();
+        let reparsed_neck_pain = reparsed.get_symptom_id("Neck pain").unwrap();
+        let reparsed_severities = reparsed.get_all_sorted_symptoms(reparsed_neck_pain).unwrap().iter().map(|s| (s.date, s.severity)).collect::<Vec<_>>();
+        let original_severities = data_man.get_all_sorted_symptoms(neck_pain).unwrap()[..2].iter().map(|s| (s.date, s.severity)).collect::<Vec<_>>();
+        assert_eq!(reparsed_severities, original_severities);
+    }
+
+    #[test]
+    fn ToBearableCsvString_ForSymptomWithLabelledScale_RoundTripsItsLabels() {
+        let text = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"8th Dec 2021","Wednesday","am","Symptom","5","Mood (Great)",""
+"8th Dec 2021","Wednesday","pm","Symptom","2","Mood (Bad)","""#;
+        let mut scales = SeverityScales::default();
+        scales.set("Mood", SeverityScale::with_labels(1, 5, "Awful,Bad,Meh,Good,Great").unwrap());
+        let mut parser = ChunkedParser::new(text.to_string(), SymptomAliases::default(), scales).unwrap();
+        while parser.parse_chunk(10).unwrap() {}
+        let data_man = parser.finish();
+
+        assert_eq!(data_man.get_symptom_names(&data_man.get_symptom_ids()), vec!["Mood"]);
+        let mood = data_man.get_symptom_id("Mood").unwrap();
+        let exported = to_bearable_csv_string(&data_man, &[mood], NaiveDate::from_ymd(2021, 12, 8)..=NaiveDate::from_ymd(2021, 12, 8)).unwrap();
+        assert_eq!(exported, text.to_string() + "\n");
+    }
+}
@@ -0,0 +1,118 @@
+use web_sys::Storage;
+use yew_d3_example::{model::symptoms::{severity_scale::{self, SeverityScales}, symptom_aliases::{self, SymptomAliases}, symptom_group::{self, SymptomGroup}}, view_model::dashboard::{self, Dashboard}};
+
+static CSV_NAME_KEY: &str = "bearable-csv-analyzer.csv_name";
+static CSV_TEXT_KEY: &str = "bearable-csv-analyzer.csv_text";
+static DASHBOARDS_KEY: &str = "bearable-csv-analyzer.dashboards";
+static SYMPTOM_ALIASES_KEY: &str = "bearable-csv-analyzer.symptom_aliases";
+static SYMPTOM_GROUPS_KEY: &str = "bearable-csv-analyzer.symptom_groups";
+static SEVERITY_SCALES_KEY: &str = "bearable-csv-analyzer.severity_scales";
+
+/// Keeps the last loaded export in the browser's local storage so it survives page reloads
+pub struct CsvStore {
+}
+
+impl CsvStore {
+    pub fn save(csv_name: &str, csv_text: &str) -> Result<(), String> {
+        let storage = local_storage()?;
+        storage.set_item(CSV_NAME_KEY, csv_name).map_err(|e| format!("{:?}", e))?;
+        storage.set_item(CSV_TEXT_KEY, csv_text).map_err(|e| {
+            // Don't leave a name behind without its data
+            let _ = storage.remove_item(CSV_NAME_KEY);
+            format!("{:?}", e)
+        })
+    }
+
+    pub fn load() -> Option<(String, String)> {
+        let storage = local_storage().ok()?;
+        let csv_name = storage.get_item(CSV_NAME_KEY).ok()??;
+        let csv_text = storage.get_item(CSV_TEXT_KEY).ok()??;
+        Some((csv_name, csv_text))
+    }
+
+    pub fn clear() -> Result<(), String> {
+        let storage = local_storage()?;
+        storage.remove_item(CSV_NAME_KEY).map_err(|e| format!("{:?}", e))?;
+        storage.remove_item(CSV_TEXT_KEY).map_err(|e| format!("{:?}", e))
+    }
+}
+
+/// Saved dashboards, kept apart from the export so clearing the data doesn't lose them
+pub struct DashboardStore {
+}
+
+impl DashboardStore {
+    pub fn save(dashboards: &[Dashboard]) -> Result<(), String> {
+        let json = dashboard::to_json(dashboards).map_err(|e| e.to_string())?;
+        local_storage()?.set_item(DASHBOARDS_KEY, &json).map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn load() -> Result<Vec<Dashboard>, String> {
+        match local_storage()?.get_item(DASHBOARDS_KEY).map_err(|e| format!("{:?}", e))? {
+            Some(json) => dashboard::from_json(&json).map_err(|e| e.to_string()),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Symptom renames, kept apart from the export so they apply to every export loaded later
+pub struct AliasStore {
+}
+
+impl AliasStore {
+    pub fn save(aliases: &SymptomAliases) -> Result<(), String> {
+        let json = symptom_aliases::to_json(aliases).map_err(|e| e.to_string())?;
+        local_storage()?.set_item(SYMPTOM_ALIASES_KEY, &json).map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn load() -> Result<SymptomAliases, String> {
+        match local_storage()?.get_item(SYMPTOM_ALIASES_KEY).map_err(|e| format!("{:?}", e))? {
+            Some(json) => symptom_aliases::from_json(&json).map_err(|e| e.to_string()),
+            None => Ok(SymptomAliases::default()),
+        }
+    }
+}
+
+/// Symptom groups, kept apart from the export like the aliases
+pub struct GroupStore {
+}
+
+impl GroupStore {
+    pub fn save(groups: &[SymptomGroup]) -> Result<(), String> {
+        let json = symptom_group::to_json(groups).map_err(|e| e.to_string())?;
+        local_storage()?.set_item(SYMPTOM_GROUPS_KEY, &json).map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn load() -> Result<Vec<SymptomGroup>, String> {
+        match local_storage()?.get_item(SYMPTOM_GROUPS_KEY).map_err(|e| format!("{:?}", e))? {
+            Some(json) => symptom_group::from_json(&json).map_err(|e| e.to_string()),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Severity scales set for symptoms, kept apart from the export like the aliases
+pub struct ScaleStore {
+}
+
+impl ScaleStore {
+    pub fn save(scales: &SeverityScales) -> Result<(), String> {
+        let json = severity_scale::to_json(scales).map_err(|e| e.to_string())?;
+        local_storage()?.set_item(SEVERITY_SCALES_KEY, &json).map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn load() -> Result<SeverityScales, String> {
+        match local_storage()?.get_item(SEVERITY_SCALES_KEY).map_err(|e| format!("{:?}", e))? {
+            Some(json) => severity_scale::from_json(&json).map_err(|e| e.to_string()),
+            None => Ok(SeverityScales::default()),
+        }
+    }
+}
+
+fn local_storage() -> Result<Storage, String> {
+    web_sys::window()
+        .ok_or_else(|| "no window".to_string())?
+        .local_storage()
+        .map_err(|e| format!("{:?}", e))?
+        .ok_or_else(|| "local storage is unavailable".to_string())
+}
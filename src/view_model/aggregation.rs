@@ -0,0 +1,30 @@
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Aggregation {
+	None,
+	DailyMax,
+	DailySum,
+}
+
+impl Aggregation {
+	pub const ALL: [Aggregation; 3] = [Aggregation::None, Aggregation::DailyMax, Aggregation::DailySum];
+
+	pub fn id(&self) -> &'static str {
+		match self {
+			Aggregation::None => "none",
+			Aggregation::DailyMax => "daily-max",
+			Aggregation::DailySum => "daily-sum",
+		}
+	}
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			Aggregation::None => "Every entry",
+			Aggregation::DailyMax => "Daily worst",
+			Aggregation::DailySum => "Daily total",
+		}
+	}
+
+	pub fn from_id(id: &str) -> Option<Aggregation> {
+		Aggregation::ALL.iter().copied().find(|aggregation| aggregation.id() == id)
+	}
+}
@@ -0,0 +1,15 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct CalendarHeatmap {
+	pub name: String,
+	pub max_value: u8,
+	pub days: Vec<DateValuePoint>
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct DateValuePoint {
+	pub date: NaiveDate,
+	pub value: u8
+}
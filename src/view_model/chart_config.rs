@@ -0,0 +1,170 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{aggregation::Aggregation, chart_kind::ChartKind};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Everything needed to draw one chart, kept in the URL fragment, e.g.
+/// `#chart=scatter&symptoms=Neck%20pain,Headache&start=2022-01-01&end=2022-01-31&aggregation=daily-max&filter=severity%20%3E%3D%203`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ChartConfig {
+	pub chart_kind: ChartKind,
+	pub symptoms: Vec<String>,
+	pub start_date: Option<NaiveDate>,
+	pub end_date: Option<NaiveDate>,
+	pub aggregation: Aggregation,
+	/// An `EntryQuery` as typed, empty when every entry is charted
+	pub filter: String,
+}
+
+impl Default for ChartConfig {
+	fn default() -> ChartConfig {
+		ChartConfig {
+			chart_kind: ChartKind::Scatter,
+			symptoms: Vec::new(),
+			start_date: None,
+			end_date: None,
+			aggregation: Aggregation::None,
+			filter: String::new(),
+		}
+	}
+}
+
+impl ChartConfig {
+	/// Unknown keys and malformed values are ignored, so old or hand-edited links still open
+	pub fn parse(fragment: &str) -> ChartConfig {
+		let mut state = ChartConfig::default();
+		for pair in fragment.trim_start_matches('#').split('&') {
+			let (key, value) = match pair.split_once('=') {
+				Some(pair) => pair,
+				None => continue,
+			};
+			match key {
+				"chart" => state.chart_kind = ChartKind::from_id(value).unwrap_or(state.chart_kind),
+				"aggregation" => state.aggregation = Aggregation::from_id(value).unwrap_or(state.aggregation),
+				"symptoms" => state.symptoms = value.split(',').filter(|s| !s.is_empty()).map(percent_decode).collect(),
+				"start" => state.start_date = NaiveDate::parse_from_str(value, DATE_FORMAT).ok(),
+				"end" => state.end_date = NaiveDate::parse_from_str(value, DATE_FORMAT).ok(),
+				"filter" => state.filter = percent_decode(value),
+				_ => {}
+			}
+		}
+		state
+	}
+
+	pub fn to_fragment(&self) -> String {
+		let mut pairs = vec![format!("chart={}", self.chart_kind.id())];
+		if !self.symptoms.is_empty() {
+			pairs.push(format!("symptoms={}", self.symptoms.iter().map(|s| percent_encode(s)).collect::<Vec<String>>().join(",")));
+		}
+		if let Some(start_date) = self.start_date {
+			pairs.push(format!("start={}", start_date.format(DATE_FORMAT)));
+		}
+		if let Some(end_date) = self.end_date {
+			pairs.push(format!("end={}", end_date.format(DATE_FORMAT)));
+		}
+		pairs.push(format!("aggregation={}", self.aggregation.id()));
+		if !self.filter.is_empty() {
+			pairs.push(format!("filter={}", percent_encode(&self.filter)));
+		}
+		format!("#{}", pairs.join("&"))
+	}
+
+	/// A short caption, e.g. `Scatter plot: Neck pain, Headache (2022-01-01 to 2022-01-31) where severity >= 3`
+	pub fn description(&self) -> String {
+		let symptoms = match self.chart_kind {
+			ChartKind::SymptomBurden => "all symptoms".to_string(),
+			_ if self.symptoms.is_empty() => "first symptom".to_string(),
+			_ => self.symptoms.join(", "),
+		};
+		let format_date = |date: Option<NaiveDate>| date.map(|d| d.format(DATE_FORMAT).to_string()).unwrap_or_else(|| "…".to_string());
+		let description = match (self.start_date, self.end_date) {
+			(None, None) => format!("{}: {}", self.chart_kind.label(), symptoms),
+			(start_date, end_date) => format!("{}: {} ({} to {})", self.chart_kind.label(), symptoms, format_date(start_date), format_date(end_date)),
+		};
+		match self.filter.as_str() {
+			"" => description,
+			filter => format!("{} where {}", description, filter),
+		}
+	}
+}
+
+/// Stored as its fragment, so saved configurations and links read the same way
+impl Serialize for ChartConfig {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.to_fragment())
+	}
+}
+
+impl<'de> Deserialize<'de> for ChartConfig {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ChartConfig, D::Error> {
+		let fragment = String::deserialize(deserializer)?;
+		Ok(ChartConfig::parse(&fragment))
+	}
+}
+
+fn percent_encode(text: &str) -> String {
+	let mut encoded = String::new();
+	for byte in text.bytes() {
+		match byte {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'(' | b')' => encoded.push(byte as char),
+			_ => encoded.push_str(&format!("%{:02X}", byte)),
+		}
+	}
+	encoded
+}
+
+fn percent_decode(text: &str) -> String {
+	let bytes = text.as_bytes();
+	let mut decoded = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+	while i < bytes.len() {
+		let escaped = bytes.get(i + 1..i + 3)
+			.and_then(|hex| std::str::from_utf8(hex).ok())
+			.and_then(|hex| u8::from_str_radix(hex, 16).ok());
+		match (bytes[i], escaped) {
+			(b'%', Some(byte)) => {
+				decoded.push(byte);
+				i += 3;
+			}
+			(byte, _) => {
+				decoded.push(byte);
+				i += 1;
+			}
+		}
+	}
+	String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn ToFragment_ForFullState_RoundTripsThroughParse() {
+		let state = ChartConfig {
+			chart_kind: ChartKind::CalendarHeatmap,
+			symptoms: vec!["Back (lower) pain".to_string(), "Neck pain, left".to_string(), "Müdigkeit".to_string()],
+			start_date: Some(NaiveDate::from_ymd(2022, 1, 1)),
+			end_date: Some(NaiveDate::from_ymd(2022, 1, 31)),
+			aggregation: Aggregation::DailyMax,
+			filter: "severity >= 3 and factor:alcohol".to_string(),
+		};
+
+		let fragment = state.to_fragment();
+
+		assert_eq!(
+			fragment,
+			"#chart=calendar&symptoms=Back%20(lower)%20pain,Neck%20pain%2C%20left,M%C3%BCdigkeit&start=2022-01-01&end=2022-01-31&aggregation=daily-max&filter=severity%20%3E%3D%203%20and%20factor%3Aalcohol"
+		);
+		assert_eq!(ChartConfig::parse(&fragment), state);
+	}
+
+	#[test]
+	fn Parse_ForMalformedValues_FallsBackToDefaults() {
+		let state = ChartConfig::parse("#chart=pie&start=yesterday&symptoms=&foo=bar&aggregation");
+
+		assert_eq!(state, ChartConfig::default());
+	}
+}
@@ -0,0 +1,33 @@
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ChartKind {
+	Scatter,
+	CalendarHeatmap,
+	SymptomBurden,
+	SeverityDistribution,
+}
+
+impl ChartKind {
+	pub const ALL: [ChartKind; 4] = [ChartKind::Scatter, ChartKind::CalendarHeatmap, ChartKind::SymptomBurden, ChartKind::SeverityDistribution];
+
+	pub fn id(&self) -> &'static str {
+		match self {
+			ChartKind::Scatter => "scatter",
+			ChartKind::CalendarHeatmap => "calendar",
+			ChartKind::SymptomBurden => "burden",
+			ChartKind::SeverityDistribution => "distribution",
+		}
+	}
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			ChartKind::Scatter => "Scatter plot",
+			ChartKind::CalendarHeatmap => "Calendar heatmap",
+			ChartKind::SymptomBurden => "Symptom burden",
+			ChartKind::SeverityDistribution => "Severity distribution",
+		}
+	}
+
+	pub fn from_id(id: &str) -> Option<ChartKind> {
+		ChartKind::ALL.iter().copied().find(|kind| kind.id() == id)
+	}
+}
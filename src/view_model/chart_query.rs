@@ -0,0 +1,255 @@
+use std::{error::Error, fmt::{self, Display}, ops::Bound};
+
+use chrono::NaiveDate;
+
+use crate::model::{data_manager::DataManager, date_map::OrderedNaiveDateTimeSpan, query::{EntryQuery, QueryError}, symptoms::symptom_id::SymptomId};
+
+use super::{
+	aggregation::Aggregation,
+	calendar_heatmap::CalendarHeatmap,
+	chart_config::ChartConfig,
+	chart_kind::ChartKind,
+	scatter_plot::ScatterPlot,
+	severity_distribution::SeverityDistribution,
+	stacked_area::StackedArea,
+};
+
+/// Whether a series gets a point per entry or per day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+	Entry,
+	Day,
+}
+
+/// How the entries of a day become the day's value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reducer {
+	Max,
+	Sum,
+}
+
+impl Reducer {
+	pub fn reduce(&self, severities: impl Iterator<Item = u8>) -> u8 {
+		match self {
+			Reducer::Max => severities.max().unwrap_or_default(),
+			Reducer::Sum => severities.fold(0, u8::saturating_add),
+		}
+	}
+}
+
+/// Whole days, open ended where a date is missing
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+	pub start: Option<NaiveDate>,
+	pub end: Option<NaiveDate>,
+}
+
+impl DateRange {
+	pub fn contains(&self, date: NaiveDate) -> bool {
+		self.start.iter().all(|start| *start <= date) && self.end.iter().all(|end| date <= *end)
+	}
+
+	/// Bounds for the `DataManager` queries, from the first day up to the day after the last one,
+	/// as a whole day sorts before every entry of the day
+	pub fn bounds(&self) -> (Bound<OrderedNaiveDateTimeSpan>, Bound<OrderedNaiveDateTimeSpan>) {
+		let start = self.start.map_or(Bound::Unbounded, |date| Bound::Included(OrderedNaiveDateTimeSpan::day(date)));
+		let end = match self.end {
+			Some(date) => date.succ_opt().map_or(Bound::Unbounded, |next| Bound::Excluded(OrderedNaiveDateTimeSpan::day(next))),
+			None => Bound::Unbounded,
+		};
+		(start, end)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChartError {
+	/// Nothing has been loaded yet
+	NoExport,
+	/// The export doesn't have any symptoms to chart
+	NoSymptoms,
+	UnknownSymptoms(Vec<String>),
+	Filter(QueryError),
+	/// The start date is after the end date
+	InvertedRange(NaiveDate, NaiveDate),
+	/// The query is fine, but there are no entries in its range
+	NothingInRange,
+}
+
+impl Display for ChartError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ChartError::NoExport => write!(f, "no export is loaded"),
+			ChartError::NoSymptoms => write!(f, "the export has no symptoms"),
+			ChartError::UnknownSymptoms(names) => write!(f, "the export has no {}", names.join(", ")),
+			ChartError::Filter(error) => write!(f, "the filter can't be read, {}", error),
+			ChartError::InvertedRange(start, end) => write!(f, "{} is after {}", start, end),
+			ChartError::NothingInRange => write!(f, "nothing was logged in that range"),
+		}
+	}
+}
+
+impl Error for ChartError {}
+
+impl From<QueryError> for ChartError {
+	fn from(error: QueryError) -> ChartError {
+		ChartError::Filter(error)
+	}
+}
+
+/// What a `ChartQuery` draws, one variant per `ChartKind`
+#[derive(Debug)]
+pub enum ChartData {
+	Scatter(ScatterPlot),
+	CalendarHeatmap(CalendarHeatmap),
+	SymptomBurden(StackedArea),
+	SeverityDistribution(SeverityDistribution),
+}
+
+impl ChartData {
+	fn is_empty(&self) -> bool {
+		match self {
+			ChartData::Scatter(scatter_plot) => scatter_plot.series.iter().all(|series| series.points.is_empty()),
+			ChartData::CalendarHeatmap(heatmap) => heatmap.days.is_empty(),
+			ChartData::SymptomBurden(burden) => burden.days.is_empty(),
+			ChartData::SeverityDistribution(distribution) => distribution.monthly.is_empty(),
+		}
+	}
+}
+
+/// Everything a chart is drawn from, with the symptoms already resolved against the loaded export.
+/// Calendar heatmaps and severity distributions show the first series only, the symptom burden every symptom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChartQuery {
+	pub chart_kind: ChartKind,
+	pub series: Vec<SymptomId>,
+	pub range: DateRange,
+	pub resolution: Resolution,
+	/// Only used at `Resolution::Day` and for heatmaps
+	pub reducer: Reducer,
+	pub filter: EntryQuery,
+}
+
+impl ChartQuery {
+	/// Without symptoms in the config, charts the alphabetically first one, which is what `ChartConfig::description` calls "first symptom"
+	pub fn from_config(config: &ChartConfig, data_manager: &DataManager) -> Result<ChartQuery, ChartError> {
+		let range = DateRange { start: config.start_date, end: config.end_date };
+		if let (Some(start), Some(end)) = (range.start, range.end) {
+			if start > end {
+				return Err(ChartError::InvertedRange(start, end));
+			}
+		}
+
+		let unknown = config.symptoms.iter().filter(|name| data_manager.get_symptom_id(name).is_none()).cloned().collect::<Vec<String>>();
+		if !unknown.is_empty() {
+			return Err(ChartError::UnknownSymptoms(unknown));
+		}
+		let series = match (config.chart_kind, config.symptoms.as_slice()) {
+			(ChartKind::SymptomBurden, _) => data_manager.get_symptom_ids(),
+			(_, []) => data_manager.get_symptom_ids().into_iter().take(1).collect(),
+			(_, names) => data_manager.get_symptom_ids_for(names),
+		};
+		if series.is_empty() {
+			return Err(ChartError::NoSymptoms);
+		}
+
+		let (resolution, reducer) = match config.aggregation {
+			Aggregation::None => (Resolution::Entry, Reducer::Max),
+			Aggregation::DailyMax => (Resolution::Day, Reducer::Max),
+			Aggregation::DailySum => (Resolution::Day, Reducer::Sum),
+		};
+		Ok(ChartQuery {
+			chart_kind: config.chart_kind,
+			series,
+			range,
+			resolution,
+			reducer,
+			filter: EntryQuery::parse(&config.filter)?,
+		})
+	}
+
+	pub fn run(&self, data_manager: &DataManager) -> Result<ChartData, ChartError> {
+		let filtered;
+		let data_manager = if self.filter.is_empty() {
+			data_manager
+		} else {
+			filtered = data_manager.filtered(&self.filter);
+			&filtered
+		};
+		let first = *self.series.first().ok_or(ChartError::NoSymptoms)?;
+		let bounds = self.range.bounds();
+
+		let chart = match self.chart_kind {
+			ChartKind::Scatter => ChartData::Scatter(match self.resolution {
+				Resolution::Entry => data_manager.get_basic_symptoms_scatterplot(&self.series, bounds),
+				Resolution::Day => data_manager.get_daily_symptoms_scatterplot(&self.series, bounds, self.reducer),
+			}.ok_or(ChartError::NoSymptoms)?),
+			ChartKind::CalendarHeatmap => ChartData::CalendarHeatmap(
+				data_manager.get_calendar_heatmap(first, bounds, self.reducer).ok_or(ChartError::NoSymptoms)?
+			),
+			ChartKind::SymptomBurden => ChartData::SymptomBurden(data_manager.get_symptom_burden(bounds)),
+			ChartKind::SeverityDistribution => {
+				let symptoms = data_manager.get_all_sorted_symptoms(first).ok_or(ChartError::NoSymptoms)?
+					.into_iter()
+					.filter(|symptom| self.range.contains(symptom.date))
+					.collect::<Vec<_>>();
+				ChartData::SeverityDistribution(SeverityDistribution::new(data_manager.get_symptom_name(first), &data_manager.get_severity_scale(first), &symptoms))
+			}
+		};
+		if chart.is_empty() {
+			return Err(ChartError::NothingInRange);
+		}
+		Ok(chart)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::model::parser::parse_into_data_manager_str;
+
+	use super::*;
+
+	const CSV: &str = r#"date,weekday,time of day,category,rating/amount,detail,notes
+"5th Jan 2022","Wednesday","am","Symptom","1","Neck pain (Mild)",""
+"5th Jan 2022","Wednesday","mid","Symptom","2","Neck pain (Moderate)",""
+"5th Jan 2022","Wednesday","pm","Symptom","3","Neck pain (Severe)",""
+"6th Jan 2022","Thursday","am","Symptom","1","Neck pain (Mild)",""
+"6th Jan 2022","Thursday","pm","Symptom","4","Neck pain (Unbearable)",""
+"7th Jan 2022","Friday","am","Symptom","4","Headache (Unbearable)","""#;
+
+	#[test]
+	fn Run_ForRangeOfWholeDays_IncludesEveryTimeOfDayOfTheLastDay() {
+		let data_man = parse_into_data_manager_str(CSV).unwrap();
+		let config = ChartConfig {
+			symptoms: vec!["Neck pain".to_string()],
+			start_date: Some(NaiveDate::from_ymd(2022, 1, 5)),
+			end_date: Some(NaiveDate::from_ymd(2022, 1, 6)),
+			aggregation: Aggregation::DailySum,
+			..ChartConfig::default()
+		};
+
+		let severities = |config: &ChartConfig| match ChartQuery::from_config(config, &data_man).and_then(|query| query.run(&data_man)) {
+			Ok(ChartData::Scatter(scatter_plot)) => scatter_plot.series[0].points.iter().map(|p| p.y).collect::<Vec<u8>>(),
+			other => panic!("expected a scatter plot, got {:?}", other),
+		};
+
+		let query = ChartQuery::from_config(&config, &data_man).unwrap();
+
+		assert_eq!((query.resolution, query.reducer), (Resolution::Day, Reducer::Sum));
+		assert_eq!(severities(&config), vec![6, 5]);
+		assert_eq!(severities(&ChartConfig { aggregation: Aggregation::None, ..config.clone() }), vec![1, 2, 3, 1, 4]);
+		assert_eq!(severities(&ChartConfig { aggregation: Aggregation::None, end_date: config.start_date, ..config }), vec![1, 2, 3]);
+	}
+
+	#[test]
+	fn FromConfigAndRun_ForBadQueries_ReturnTypedErrors() {
+		let data_man = parse_into_data_manager_str(CSV).unwrap();
+		let date = |day| Some(NaiveDate::from_ymd(2022, 1, day));
+		let run = |config: ChartConfig| ChartQuery::from_config(&config, &data_man).and_then(|query| query.run(&data_man)).map(|_| ());
+
+		assert_eq!(run(ChartConfig { symptoms: vec!["Nausea".to_string()], ..ChartConfig::default() }), Err(ChartError::UnknownSymptoms(vec!["Nausea".to_string()])));
+		assert_eq!(run(ChartConfig { start_date: date(7), end_date: date(5), ..ChartConfig::default() }), Err(ChartError::InvertedRange(date(7).unwrap(), date(5).unwrap())));
+		assert_eq!(run(ChartConfig { start_date: date(8), ..ChartConfig::default() }), Err(ChartError::NothingInRange));
+		assert_eq!(run(ChartConfig { filter: "severity >".to_string(), ..ChartConfig::default() }), Err(ChartError::Filter(QueryError::UnexpectedEnd)));
+		assert_eq!(run(ChartConfig::default()), Ok(()));
+	}
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use super::chart_config::ChartConfig;
+
+/// A named set of charts drawn side by side in a grid
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub struct Dashboard {
+	pub name: String,
+	pub charts: Vec<ChartConfig>,
+}
+
+impl Dashboard {
+	/// The id of the `<svg>` the chart at `index` is drawn into
+	pub fn chart_id(index: usize) -> String {
+		format!("dashboard-chart-{}", index)
+	}
+}
+
+/// Adds the dashboard, replacing any saved one with the same name
+pub fn save_dashboard(dashboards: &mut Vec<Dashboard>, dashboard: Dashboard) {
+	match dashboards.iter_mut().find(|d| d.name == dashboard.name) {
+		Some(existing) => *existing = dashboard,
+		None => {
+			dashboards.push(dashboard);
+			dashboards.sort_by(|a, b| a.name.cmp(&b.name));
+		}
+	}
+}
+
+pub fn to_json(dashboards: &[Dashboard]) -> serde_json::Result<String> {
+	serde_json::to_string(dashboards)
+}
+
+pub fn from_json(json: &str) -> serde_json::Result<Vec<Dashboard>> {
+	serde_json::from_str(json)
+}
+
+#[cfg(test)]
+mod tests {
+	use chrono::NaiveDate;
+
+	use crate::view_model::{aggregation::Aggregation, chart_kind::ChartKind};
+
+	use super::*;
+
+	fn dashboard(name: &str, symptom: &str) -> Dashboard {
+		Dashboard {
+			name: name.to_string(),
+			charts: vec![
+				ChartConfig {
+					chart_kind: ChartKind::Scatter,
+					symptoms: vec![symptom.to_string()],
+					start_date: Some(NaiveDate::from_ymd(2022, 1, 1)),
+					end_date: None,
+					aggregation: Aggregation::DailySum,
+					filter: String::new(),
+				},
+				ChartConfig { chart_kind: ChartKind::SymptomBurden, ..ChartConfig::default() },
+			],
+		}
+	}
+
+	#[test]
+	fn ToJson_ForDashboards_RoundTripsThroughFromJson() {
+		let dashboards = vec![dashboard("Flares", "Neck pain"), dashboard("Sleep", "Insomnia")];
+
+		let json = to_json(&dashboards).unwrap();
+
+		assert!(json.contains("\"#chart=scatter&symptoms=Neck%20pain&start=2022-01-01&aggregation=daily-sum\""));
+		assert_eq!(from_json(&json).unwrap(), dashboards);
+	}
+
+	#[test]
+	fn SaveDashboard_ForExistingName_ReplacesIt() {
+		let mut dashboards = vec![dashboard("Sleep", "Insomnia")];
+
+		save_dashboard(&mut dashboards, dashboard("Flares", "Neck pain"));
+		save_dashboard(&mut dashboards, dashboard("Sleep", "Fatigue"));
+
+		assert_eq!(dashboards.iter().map(|d| d.name.as_str()).collect::<Vec<_>>(), vec!["Flares", "Sleep"]);
+		assert_eq!(dashboards[1].charts[0].symptoms, vec!["Fatigue".to_string()]);
+	}
+}
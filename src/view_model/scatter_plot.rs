@@ -1,13 +1,42 @@
 use chrono::{NaiveDateTime};
 use serde::Serialize;
 
+use crate::model::{symptoms::severity_scale::SeverityScale, time_of_day::TimeOfDay};
+
+/// d3's `schemeCategory10`, so series colors match the rest of the d3 defaults
+pub const SERIES_COLORS: [&str; 10] = [
+	"#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f", "#bcbd22", "#17becf",
+];
+
+#[derive(Debug, Serialize)]
 pub struct ScatterPlot {
+	pub series: Vec<ScatterPlotSeries>
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScatterPlotSeries {
+	pub name: String,
+	pub color: String,
+	/// What the y values mean, the chart labels its ticks with the scale's levels
+	pub scale: SeverityScale,
 	pub points: Vec<DateTimeValuePoint>
 }
 
+impl ScatterPlotSeries {
+	pub fn new(name: &str, index: usize, scale: SeverityScale, points: Vec<DateTimeValuePoint>) -> ScatterPlotSeries {
+		ScatterPlotSeries {
+			name: name.to_string(),
+			color: SERIES_COLORS[index % SERIES_COLORS.len()].to_string(),
+			scale,
+			points
+		}
+	}
+}
 
 #[derive(Debug, Serialize)]
 pub struct DateTimeValuePoint {
 	pub x: NaiveDateTime,
-	pub y: u8
-}
\ No newline at end of file
+	pub y: u8,
+	pub time_of_day: TimeOfDay,
+	pub notes: String
+}
@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::model::symptoms::{severity_scale::SeverityScale, symptom::Symptom};
+
+#[derive(Debug, Serialize)]
+pub struct SeverityDistribution {
+	pub name: String,
+	pub histogram: Vec<HistogramBin>,
+	pub monthly: Vec<BoxPlot>
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct HistogramBin {
+	pub severity: u8,
+	/// The scale's name for the severity, if it has one
+	pub label: Option<String>,
+	pub count: usize
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct BoxPlot {
+	pub label: String,
+	pub count: usize,
+	pub min: f64,
+	pub q1: f64,
+	pub median: f64,
+	pub q3: f64,
+	pub max: f64
+}
+
+impl SeverityDistribution {
+	/// Expects the symptoms in date order, as returned by `DataManager::get_all_sorted_symptoms`.
+	/// The histogram has a bin for every value on the scale, and for any logged outside it.
+	pub fn new(name: &str, scale: &SeverityScale, symptoms: &[&Symptom]) -> SeverityDistribution {
+		let min_severity = symptoms.iter().map(|s| s.severity).min().map_or(scale.min, |min| min.min(scale.min));
+		let max_severity = symptoms.iter().map(|s| s.severity).max().map_or(scale.max, |max| max.max(scale.max));
+		let histogram = (min_severity..=max_severity)
+			.map(|severity| HistogramBin {
+				severity,
+				label: scale.label(severity).map(str::to_string),
+				count: symptoms.iter().filter(|s| s.severity == severity).count()
+			})
+			.collect();
+
+		let mut months = BTreeMap::<String, Vec<u8>>::new();
+		for symptom in symptoms {
+			months.entry(symptom.date.format("%Y-%m").to_string()).or_default().push(symptom.severity);
+		}
+		let monthly = months.into_iter()
+			.filter_map(|(label, severities)| BoxPlot::new(label, severities))
+			.collect();
+
+		SeverityDistribution {
+			name: name.to_string(),
+			histogram,
+			monthly
+		}
+	}
+}
+
+impl BoxPlot {
+	pub fn new(label: String, mut values: Vec<u8>) -> Option<BoxPlot> {
+		values.sort_unstable();
+		Some(BoxPlot {
+			label,
+			count: values.len(),
+			min: *values.first()? as f64,
+			q1: quantile(&values, 0.25)?,
+			median: quantile(&values, 0.5)?,
+			q3: quantile(&values, 0.75)?,
+			max: *values.last()? as f64
+		})
+	}
+}
+
+/// Linearly interpolated quantile of already sorted values, matching `d3.quantile`
+fn quantile(sorted: &[u8], p: f64) -> Option<f64> {
+	let last = sorted.len().checked_sub(1)?;
+	let position = last as f64 * p;
+	let lower = position.floor() as usize;
+	let upper = (lower + 1).min(last);
+	let fraction = position - lower as f64;
+	Some(sorted[lower] as f64 + (sorted[upper] as f64 - sorted[lower] as f64) * fraction)
+}
+
+#[cfg(test)]
+mod tests {
+	use chrono::NaiveDate;
+
+	use crate::model::{symptoms::symptom_id::SymptomRegistry, time_of_day::TimeOfDay};
+
+	use super::*;
+
+	#[test]
+	fn BoxPlot_ForEvenNumberOfValues_InterpolatesQuartiles() {
+		let box_plot = BoxPlot::new("2022-01".to_string(), vec![4, 1, 2, 3]).unwrap();
+
+		assert_eq!(box_plot.min, 1.0);
+		assert_eq!(box_plot.q1, 1.75);
+		assert_eq!(box_plot.median, 2.5);
+		assert_eq!(box_plot.q3, 3.25);
+		assert_eq!(box_plot.max, 4.0);
+	}
+
+	#[test]
+	fn New_ForSymptomsAcrossMonths_CountsSeveritiesAndSplitsByMonth() {
+		let neck_pain = SymptomRegistry::default().intern("Neck pain");
+		let symptom = |date: NaiveDate, severity: u8| Symptom {
+			date,
+			id: neck_pain,
+			severity,
+			time_of_day: TimeOfDay::AM,
+			notes: String::new(),
+		};
+		let symptoms = [
+			symptom(NaiveDate::from_ymd(2022, 1, 5), 1),
+			symptom(NaiveDate::from_ymd(2022, 1, 6), 3),
+			symptom(NaiveDate::from_ymd(2022, 2, 1), 3),
+		];
+
+		let distribution = SeverityDistribution::new("Neck pain", &SeverityScale::default(), &symptoms.iter().collect::<Vec<&Symptom>>());
+
+		let counts = distribution.histogram.iter().map(|b| b.count).collect::<Vec<usize>>();
+		assert_eq!(counts, vec![0, 1, 0, 2, 0]);
+		assert_eq!(distribution.histogram[3].label.as_deref(), Some("Severe"));
+		let labels = distribution.monthly.iter().map(|b| b.label.as_str()).collect::<Vec<&str>>();
+		assert_eq!(labels, vec!["2022-01", "2022-02"]);
+		assert_eq!(distribution.monthly[0].median, 2.0);
+	}
+
+	#[test]
+	fn New_ForSymptomOnItsOwnScale_BinsOverTheScale() {
+		let mood = SymptomRegistry::default().intern("Mood");
+		let symptoms = [Symptom { date: NaiveDate::from_ymd(2022, 1, 5), id: mood, severity: 4, time_of_day: TimeOfDay::AM, notes: String::new() }];
+		let scale = SeverityScale::with_labels(1, 5, "Awful,Bad,Meh,Good,Great").unwrap();
+
+		let distribution = SeverityDistribution::new("Mood", &scale, &symptoms.iter().collect::<Vec<&Symptom>>());
+
+		let bins = distribution.histogram.iter().map(|b| (b.severity, b.label.as_deref(), b.count)).collect::<Vec<_>>();
+		assert_eq!(bins, vec![(1, Some("Awful"), 0), (2, Some("Bad"), 0), (3, Some("Meh"), 0), (4, Some("Good"), 1), (5, Some("Great"), 0)]);
+		let pain_score = SeverityDistribution::new("Pain score", &SeverityScale::numeric(0, 10), &[]);
+		assert_eq!(pain_score.histogram.len(), 11);
+	}
+}
@@ -0,0 +1,21 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// One row per day, with `values` lined up with `series`
+#[derive(Debug, Serialize)]
+pub struct StackedArea {
+	pub series: Vec<StackedAreaSeries>,
+	pub days: Vec<StackedAreaDay>
+}
+
+#[derive(Debug, Serialize)]
+pub struct StackedAreaSeries {
+	pub name: String,
+	pub color: String
+}
+
+#[derive(Debug, Serialize)]
+pub struct StackedAreaDay {
+	pub date: NaiveDate,
+	pub values: Vec<u8>
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use yew_agent::{Agent, AgentLink, HandlerId, Public};
+
+use crate::model::{data_manager::DataManager, parser::ChunkedParser, schema::Schema, symptoms::{severity_scale::SeverityScales, symptom_aliases::SymptomAliases}};
+
+/// Small enough that progress updates and cancelling stay responsive on multi-year exports
+const ROWS_PER_CHUNK: usize = 2000;
+
+#[derive(Serialize, Deserialize)]
+pub struct ParseRequest {
+    pub csv_text: String,
+    pub aliases: SymptomAliases,
+    pub scales: SeverityScales,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum ParseResponse {
+    /// How much of the export has been parsed, from 0 to 1
+    Progress(f32),
+    /// The parsed export and the columns it was read from
+    Parsed(Box<DataManager>, Schema),
+    Failed(String),
+}
+
+pub enum Msg {
+    ParseNextChunk,
+}
+
+/// Parses an export into a `DataManager` off the main thread.
+/// Dropping the bridge cancels the parse, the worker stops at the end of the current chunk.
+pub struct ParseWorker {
+    link: AgentLink<Self>,
+    job: Option<(HandlerId, ChunkedParser)>,
+}
+
+impl Agent for ParseWorker {
+    type Reach = Public<Self>;
+    type Message = Msg;
+    type Input = ParseRequest;
+    type Output = ParseResponse;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        ParseWorker { link, job: None }
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match msg {
+            Msg::ParseNextChunk => {
+                let (id, parser) = match &mut self.job {
+                    Some(job) => job,
+                    None => return,
+                };
+                match parser.parse_chunk(ROWS_PER_CHUNK) {
+                    Ok(true) => {
+                        self.link.respond(*id, ParseResponse::Progress(parser.progress()));
+                        self.link.send_future(yield_to_event_loop());
+                    }
+                    Ok(false) => if let Some((id, parser)) = self.job.take() {
+                        let schema = parser.schema().clone();
+                        self.link.respond(id, ParseResponse::Parsed(Box::new(parser.finish()), schema));
+                    }
+                    Err(e) => {
+                        self.link.respond(*id, ParseResponse::Failed(e.to_string()));
+                        self.job = None;
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_input(&mut self, request: Self::Input, id: HandlerId) {
+        match ChunkedParser::new(request.csv_text, request.aliases, request.scales) {
+            Ok(parser) => {
+                self.job = Some((id, parser));
+                self.link.send_message(Msg::ParseNextChunk);
+            }
+            Err(e) => self.link.respond(id, ParseResponse::Failed(e.to_string())),
+        }
+    }
+
+    fn disconnected(&mut self, id: HandlerId) {
+        if matches!(&self.job, Some((job_id, _)) if *job_id == id) {
+            self.job = None;
+        }
+    }
+
+    fn name_of_resource() -> &'static str {
+        "worker.js"
+    }
+
+    fn resource_path_is_relative() -> bool {
+        true
+    }
+}
+
+/// Waits on a timeout rather than a resolved promise, so messages like the bridge disconnecting get handled between chunks
+async fn yield_to_event_loop() -> Msg {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let global = js_sys::global().unchecked_into::<web_sys::WorkerGlobalScope>();
+        global.set_timeout_with_callback(&resolve).expect("workers can set timeouts");
+    });
+    let _ = JsFuture::from(promise).await;
+    Msg::ParseNextChunk
+}